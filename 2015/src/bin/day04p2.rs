@@ -1,8 +1,6 @@
-use common::generate;
-
 use advent_of_code_2015::day04::day04p2;
 
 fn main() {
-    let i = generate::ints(include_str!("../../input/day04.txt"));
-    dbg!(day04p2(&i));
+    let i = include_str!("../../input/day04.txt");
+    dbg!(day04p2(i));
 }
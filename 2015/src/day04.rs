@@ -1,9 +1,11 @@
-pub fn day04p1(input: &[i32]) -> i32 {
-    input.len() as i32
+use common::hash::find_nonce;
+
+pub fn day04p1(input: &str) -> u64 {
+    find_nonce(input.trim(), 5)
 }
 
-pub fn day04p2(input: &[i32]) -> i32 {
-    input.len() as i32
+pub fn day04p2(input: &str) -> u64 {
+    find_nonce(input.trim(), 6)
 }
 
 #[cfg(test)]
@@ -12,11 +14,12 @@ mod tests {
 
     #[test]
     fn part1_examples() {
-        assert_eq!(0, day04p1(&[]))
+        assert_eq!(609043, day04p1("abcdef"));
+        assert_eq!(1048970, day04p1("pqrstuv"));
     }
 
     #[test]
     fn part2_examples() {
-        assert_eq!(0, day04p2(&[]))
+        assert_eq!(6742839, day04p2("abcdef"));
     }
 }
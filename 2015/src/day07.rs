@@ -1,9 +1,29 @@
-pub fn day07p1(input: &[i32]) -> usize {
-    input.len()
+use common::expr::{Definitions, Expr, Grammar};
+
+pub fn day07p1(input: &str) -> u16 {
+    parse(input).eval("a").unwrap()
+}
+
+pub fn day07p2(input: &str) -> u16 {
+    let mut circuit = parse(input);
+    let a = circuit.eval("a").unwrap();
+    circuit.insert("b", Expr::Num(a));
+    circuit.eval("a").unwrap()
 }
 
-pub fn day07p2(input: &[i32]) -> usize {
-    input.len()
+fn parse(input: &str) -> Definitions<u16> {
+    let grammar = Grammar::<u16>::new()
+        .binary("AND", 1, |a, b| a & b)
+        .binary("OR", 1, |a, b| a | b)
+        .binary("LSHIFT", 1, |a, b| a << b)
+        .binary("RSHIFT", 1, |a, b| a >> b)
+        .prefix("NOT", 2, |a| !a);
+
+    Definitions::parse(input, &grammar, |line| {
+        let (expr, wire) = line.rsplit_once(" -> ").unwrap();
+        (wire, expr)
+    })
+    .unwrap()
 }
 
 #[cfg(test)]
@@ -12,11 +32,35 @@ mod tests {
 
     #[test]
     fn part1_examples() {
-        assert_eq!(0, day07p1(&[]))
+        let circuit = parse(INPUT);
+        let signals = [
+            ("d", 72),
+            ("e", 507),
+            ("f", 492),
+            ("g", 114),
+            ("h", 65412),
+            ("i", 65079),
+            ("x", 123),
+            ("y", 456),
+        ];
+        for (wire, signal) in signals {
+            assert_eq!(Some(signal), circuit.eval(wire), "wire {wire}");
+        }
+
+        assert_eq!(6, day07p1("b LSHIFT 1 -> a\n3 -> b"));
     }
 
     #[test]
     fn part2_examples() {
-        assert_eq!(0, day07p2(&[]))
+        assert_eq!(12, day07p2("b LSHIFT 1 -> a\n3 -> b"));
     }
+
+    const INPUT: &str = "123 -> x
+456 -> y
+x AND y -> d
+x OR y -> e
+x LSHIFT 2 -> f
+y RSHIFT 2 -> g
+NOT x -> h
+NOT y -> i";
 }
@@ -1,9 +1,21 @@
-pub fn day12p1(input: &[i32]) -> usize {
-    input.len()
+use common::json::Value;
+
+pub fn day12p1(input: &str) -> i64 {
+    sum(input, false)
+}
+
+pub fn day12p2(input: &str) -> i64 {
+    sum(input, true)
 }
 
-pub fn day12p2(input: &[i32]) -> usize {
-    input.len()
+/// Sum of all numbers in the document, optionally skipping objects with a "red" property
+fn sum(input: &str, skip_red: bool) -> i64 {
+    let doc: Value = input.trim().parse().unwrap();
+    doc.fold(&mut |v, children: Vec<i64>| match v {
+        Value::Number(_) => v.as_i64().unwrap(),
+        Value::Object(o) if skip_red && o.iter().any(|(_, v)| v.as_str() == Some("red")) => 0,
+        _ => children.into_iter().sum(),
+    })
 }
 
 #[cfg(test)]
@@ -12,11 +24,21 @@ mod tests {
 
     #[test]
     fn part1_examples() {
-        assert_eq!(0, day12p1(&[]))
+        assert_eq!(6, day12p1("[1,2,3]"));
+        assert_eq!(6, day12p1(r#"{"a":2,"b":4}"#));
+        assert_eq!(3, day12p1("[[[3]]]"));
+        assert_eq!(3, day12p1(r#"{"a":{"b":4},"c":-1}"#));
+        assert_eq!(0, day12p1(r#"{"a":[-1,1]}"#));
+        assert_eq!(0, day12p1(r#"[-1,{"a":1}]"#));
+        assert_eq!(0, day12p1("[]"));
+        assert_eq!(0, day12p1("{}"));
     }
 
     #[test]
     fn part2_examples() {
-        assert_eq!(0, day12p2(&[]))
+        assert_eq!(6, day12p2("[1,2,3]"));
+        assert_eq!(4, day12p2(r#"[1,{"c":"red","b":2},3]"#));
+        assert_eq!(0, day12p2(r#"{"d":"red","e":[1,2,3,4],"f":5}"#));
+        assert_eq!(6, day12p2(r#"[1,"red",5]"#));
     }
 }
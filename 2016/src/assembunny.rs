@@ -0,0 +1,280 @@
+//! The assembunny interpreter shared by days 12, 23 and 25
+//!
+//! `tgl` rewrites the program while it runs, so instead of rewriting loops up front, the
+//! interpreter recognises addition and multiplication loops whenever it is about to enter one,
+//! and replaces them by a single step.
+//!
+//! # Examples
+//!
+//! ```
+//! use advent_of_code_2016::assembunny::Machine;
+//!
+//! let mut m = Machine::parse("cpy 41 a\ninc a\ninc a\ndec a\njnz a 2\ndec a\n");
+//! m.run();
+//! assert_eq!(42, m.reg('a'));
+//! ```
+
+use std::fmt;
+
+/// The values held in registers
+pub type Word = i64;
+
+const REGISTERS: [char; 4] = ['a', 'b', 'c', 'd'];
+
+/// An instruction argument
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arg {
+    Reg(usize),
+    Imm(Word),
+}
+
+impl Arg {
+    fn parse(s: &str) -> Self {
+        match s.chars().collect::<Vec<_>>()[..] {
+            [c] if REGISTERS.contains(&c) => Arg::Reg(c as usize - 'a' as usize),
+            _ => Arg::Imm(
+                s.parse()
+                    .unwrap_or_else(|_| panic!("invalid argument {s:?}")),
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Arg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Arg::Reg(r) => write!(f, "{}", REGISTERS[r]),
+            Arg::Imm(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+/// A single instruction
+///
+/// Arguments that have to be registers are still stored as [`Arg`]s, because `tgl` can turn a
+/// valid instruction into one that has to be skipped, like `cpy 1 2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Cpy(Arg, Arg),
+    Inc(Arg),
+    Dec(Arg),
+    Jnz(Arg, Arg),
+    Tgl(Arg),
+    Out(Arg),
+}
+
+impl Instruction {
+    pub fn parse(line: &str) -> Self {
+        let parts: Vec<_> = line.split_whitespace().collect();
+        let arg = |i: usize| Arg::parse(parts[i]);
+        match parts[0] {
+            "cpy" => Instruction::Cpy(arg(1), arg(2)),
+            "inc" => Instruction::Inc(arg(1)),
+            "dec" => Instruction::Dec(arg(1)),
+            "jnz" => Instruction::Jnz(arg(1), arg(2)),
+            "tgl" => Instruction::Tgl(arg(1)),
+            "out" => Instruction::Out(arg(1)),
+            _ => panic!("unknown instruction {line:?}"),
+        }
+    }
+
+    /// The instruction `tgl` turns this one into
+    fn toggled(self) -> Self {
+        match self {
+            Instruction::Inc(x) => Instruction::Dec(x),
+            Instruction::Dec(x) | Instruction::Tgl(x) | Instruction::Out(x) => Instruction::Inc(x),
+            Instruction::Jnz(x, y) => Instruction::Cpy(x, y),
+            Instruction::Cpy(x, y) => Instruction::Jnz(x, y),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Cpy(x, y) => write!(f, "cpy {x} {y}"),
+            Instruction::Inc(x) => write!(f, "inc {x}"),
+            Instruction::Dec(x) => write!(f, "dec {x}"),
+            Instruction::Jnz(x, y) => write!(f, "jnz {x} {y}"),
+            Instruction::Tgl(x) => write!(f, "tgl {x}"),
+            Instruction::Out(x) => write!(f, "out {x}"),
+        }
+    }
+}
+
+/// A program together with its registers
+#[derive(Debug, Clone)]
+pub struct Machine {
+    program: Vec<Instruction>,
+    regs: [Word; 4],
+    pc: usize,
+}
+
+impl Machine {
+    pub fn new(program: Vec<Instruction>) -> Self {
+        Self {
+            program,
+            regs: [0; 4],
+            pc: 0,
+        }
+    }
+
+    /// Parse a program with one instruction per line
+    pub fn parse(source: &str) -> Self {
+        Self::new(
+            source
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(Instruction::parse)
+                .collect(),
+        )
+    }
+
+    /// Set the initial value of a register
+    pub fn with_register(mut self, name: char, value: Word) -> Self {
+        self.regs[Self::index(name)] = value;
+        self
+    }
+
+    /// The value of register `name`
+    pub fn reg(&self, name: char) -> Word {
+        self.regs[Self::index(name)]
+    }
+
+    fn index(name: char) -> usize {
+        REGISTERS
+            .iter()
+            .position(|&r| r == name)
+            .unwrap_or_else(|| panic!("unknown register {name:?}"))
+    }
+
+    pub fn program(&self) -> &[Instruction] {
+        &self.program
+    }
+
+    /// Whether the program counter has left the program
+    pub fn halted(&self) -> bool {
+        self.pc >= self.program.len()
+    }
+
+    /// Run until the program halts, ignoring its output
+    pub fn run(&mut self) {
+        while !self.halted() {
+            self.step();
+        }
+    }
+
+    /// Run until the program outputs a value, or halts
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use advent_of_code_2016::assembunny::Machine;
+    ///
+    /// let mut m = Machine::parse("out 0\nout 1\njnz 1 -2\n");
+    /// let signal: Vec<_> = std::iter::from_fn(|| m.next_output()).take(5).collect();
+    /// assert_eq!(vec![0, 1, 0, 1, 0], signal);
+    /// ```
+    pub fn next_output(&mut self) -> Option<Word> {
+        while !self.halted() {
+            if let Some(out) = self.step() {
+                return Some(out);
+            }
+        }
+        None
+    }
+
+    /// Execute one instruction, or one recognised loop; returns the value it outputs, if any
+    pub fn step(&mut self) -> Option<Word> {
+        if self.multiply() || self.add() {
+            return None;
+        }
+
+        let mut next = self.pc + 1;
+        let mut output = None;
+        match self.program[self.pc] {
+            Instruction::Cpy(x, Arg::Reg(r)) => self.regs[r] = self.value(x),
+            Instruction::Inc(Arg::Reg(r)) => self.regs[r] += 1,
+            Instruction::Dec(Arg::Reg(r)) => self.regs[r] -= 1,
+            Instruction::Jnz(x, y) => {
+                if self.value(x) != 0 {
+                    next = self.pc.wrapping_add_signed(self.value(y) as isize);
+                }
+            }
+            Instruction::Tgl(x) => {
+                let target = self.pc.wrapping_add_signed(self.value(x) as isize);
+                if let Some(i) = self.program.get_mut(target) {
+                    *i = i.toggled();
+                }
+            }
+            Instruction::Out(x) => output = Some(self.value(x)),
+            // made invalid by `tgl`
+            Instruction::Cpy(..) | Instruction::Inc(_) | Instruction::Dec(_) => {}
+        }
+        self.pc = next;
+        output
+    }
+
+    fn value(&self, arg: Arg) -> Word {
+        match arg {
+            Arg::Reg(r) => self.regs[r],
+            Arg::Imm(v) => v,
+        }
+    }
+
+    /// `inc x; dec y; jnz y -2`, or with `inc` and `dec` swapped: `x += y; y = 0`
+    fn add(&mut self) -> bool {
+        use Instruction::*;
+
+        let Some(&[a, b, Jnz(Arg::Reg(y), Arg::Imm(-2))]) = self.program.get(self.pc..self.pc + 3)
+        else {
+            return false;
+        };
+        let x = match (a, b) {
+            (Inc(Arg::Reg(x)), Dec(Arg::Reg(d))) | (Dec(Arg::Reg(d)), Inc(Arg::Reg(x)))
+                if d == y && x != y =>
+            {
+                x
+            }
+            _ => return false,
+        };
+        if self.regs[y] <= 0 {
+            return false;
+        }
+
+        self.regs[x] += self.regs[y];
+        self.regs[y] = 0;
+        self.pc += 3;
+        true
+    }
+
+    /// `cpy s c; inc a; dec c; jnz c -2; dec d; jnz d -5`: `a += s * d; c = 0; d = 0`
+    fn multiply(&mut self) -> bool {
+        use Instruction::*;
+
+        let Some(window) = self.program.get(self.pc..self.pc + 6) else {
+            return false;
+        };
+        let &[Cpy(src, Arg::Reg(c)), Inc(Arg::Reg(a)), Dec(Arg::Reg(c2)), Jnz(Arg::Reg(c3), Arg::Imm(-2)), Dec(Arg::Reg(d)), Jnz(Arg::Reg(d2), Arg::Imm(-5))] =
+            window
+        else {
+            return false;
+        };
+
+        let same = c == c2 && c == c3 && d == d2;
+        let distinct = a != c && a != d && c != d && ![a, c, d].map(Arg::Reg).contains(&src);
+        if !same || !distinct {
+            return false;
+        }
+        let (s, times) = (self.value(src), self.regs[d]);
+        if s <= 0 || times <= 0 {
+            return false;
+        }
+
+        self.regs[a] += s * times;
+        self.regs[c] = 0;
+        self.regs[d] = 0;
+        self.pc += 6;
+        true
+    }
+}
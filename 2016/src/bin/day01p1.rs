@@ -0,0 +1,6 @@
+use advent_of_code_2016::day01::day01p1;
+
+fn main() {
+    let input = include_str!("../../input/day01.txt");
+    dbg!(day01p1(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2016::day02::day02p1;
+
+fn main() {
+    let input = include_str!("../../input/day02.txt");
+    dbg!(day02p1(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2016::day03::day03p2;
+
+fn main() {
+    let input = include_str!("../../input/day03.txt");
+    dbg!(day03p2(input));
+}
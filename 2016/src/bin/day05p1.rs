@@ -0,0 +1,6 @@
+use advent_of_code_2016::day05::day05p1;
+
+fn main() {
+    let input = include_str!("../../input/day05.txt");
+    dbg!(day05p1(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2016::day06::day06p1;
+
+fn main() {
+    let input = include_str!("../../input/day06.txt");
+    dbg!(day06p1(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2016::day07::day07p1;
+
+fn main() {
+    let input = include_str!("../../input/day07.txt");
+    dbg!(day07p1(input));
+}
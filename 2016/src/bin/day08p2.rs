@@ -0,0 +1,6 @@
+use advent_of_code_2016::day08::day08p2;
+
+fn main() {
+    let input = include_str!("../../input/day08.txt");
+    dbg!(day08p2(input));
+}
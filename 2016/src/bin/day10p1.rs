@@ -0,0 +1,6 @@
+use advent_of_code_2016::day10::day10p1;
+
+fn main() {
+    let input = include_str!("../../input/day10.txt");
+    dbg!(day10p1(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2016::day12::day12p2;
+
+fn main() {
+    let input = include_str!("../../input/day12.txt");
+    dbg!(day12p2(input));
+}
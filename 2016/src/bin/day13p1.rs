@@ -0,0 +1,6 @@
+use advent_of_code_2016::day13::day13p1;
+
+fn main() {
+    let input = include_str!("../../input/day13.txt");
+    dbg!(day13p1(input));
+}
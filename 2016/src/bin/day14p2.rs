@@ -0,0 +1,6 @@
+use advent_of_code_2016::day14::day14p2;
+
+fn main() {
+    let input = include_str!("../../input/day14.txt");
+    dbg!(day14p2(input));
+}
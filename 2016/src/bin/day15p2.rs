@@ -0,0 +1,6 @@
+use advent_of_code_2016::day15::day15p2;
+
+fn main() {
+    let input = include_str!("../../input/day15.txt");
+    dbg!(day15p2(input));
+}
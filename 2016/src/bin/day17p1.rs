@@ -0,0 +1,6 @@
+use advent_of_code_2016::day17::day17p1;
+
+fn main() {
+    let input = include_str!("../../input/day17.txt");
+    dbg!(day17p1(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2016::day19::day19p2;
+
+fn main() {
+    let input = include_str!("../../input/day19.txt");
+    dbg!(day19p2(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2016::day20::day20p2;
+
+fn main() {
+    let input = include_str!("../../input/day20.txt");
+    dbg!(day20p2(input));
+}
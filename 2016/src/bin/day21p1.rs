@@ -0,0 +1,6 @@
+use advent_of_code_2016::day21::day21p1;
+
+fn main() {
+    let input = include_str!("../../input/day21.txt");
+    dbg!(day21p1(input));
+}
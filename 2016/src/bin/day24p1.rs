@@ -0,0 +1,6 @@
+use advent_of_code_2016::day24::day24p1;
+
+fn main() {
+    let input = include_str!("../../input/day24.txt");
+    dbg!(day24p1(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2016::day25::day25p1;
+
+fn main() {
+    let input = include_str!("../../input/day25.txt");
+    dbg!(day25p1(input));
+}
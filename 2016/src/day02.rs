@@ -0,0 +1,90 @@
+use common::{Grid, GridCoord};
+
+const SQUARE: &str = "123
+456
+789
+";
+
+const DIAMOND: &str = "..1..
+.234.
+56789
+.ABC.
+..D..
+";
+
+pub fn day02p1(input: &str) -> String {
+    Keypad::parse(SQUARE).code(input)
+}
+
+pub fn day02p2(input: &str) -> String {
+    Keypad::parse(DIAMOND).code(input)
+}
+
+/// A keypad with keys laid out on a grid, where dots are not keys
+struct Keypad {
+    keys: Grid<Option<char>>,
+}
+
+impl Keypad {
+    fn parse(layout: &str) -> Self {
+        Self {
+            keys: Grid::parse(layout, |c| (c != '.').then_some(c)),
+        }
+    }
+
+    /// Follow each line of instructions from the last button pressed, starting at `5`.
+    fn code(&self, instructions: &str) -> String {
+        let mut pos = self
+            .keys
+            .iter()
+            .find(|(_, &k)| k == Some('5'))
+            .map(|(pos, _)| pos)
+            .expect("a 5 key");
+
+        instructions
+            .lines()
+            .map(|line| {
+                pos = line.chars().fold(pos, |pos, dir| self.walk(pos, dir));
+                self.keys[pos].unwrap()
+            })
+            .collect()
+    }
+
+    /// The button in direction `dir`, or the same button if there is none.
+    fn walk(&self, pos: GridCoord<usize>, dir: char) -> GridCoord<usize> {
+        let (dx, dy) = match dir {
+            'U' => (0, -1),
+            'D' => (0, 1),
+            'L' => (-1, 0),
+            'R' => (1, 0),
+            _ => panic!("unknown direction {dir:?}"),
+        };
+        pos.x
+            .checked_add_signed(dx)
+            .zip(pos.y.checked_add_signed(dy))
+            .map(|(x, y)| GridCoord::new(x, y))
+            .filter(|&next| self.keys.get(next).is_some_and(Option::is_some))
+            .unwrap_or(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!("1985", day02p1(INPUT));
+    }
+
+    #[test]
+    fn part2_examples() {
+        assert_eq!("5DB3", day02p2(INPUT));
+    }
+
+    const INPUT: &str = "ULL
+RRDDD
+LURDL
+UUUUD
+";
+}
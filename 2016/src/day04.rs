@@ -0,0 +1,98 @@
+const NORTH_POLE_STORAGE: &str = "northpole object storage";
+
+pub fn day04p1(input: &str) -> u32 {
+    input
+        .lines()
+        .map(Room::parse)
+        .filter(Room::is_real)
+        .map(|r| r.sector)
+        .sum()
+}
+
+pub fn day04p2(input: &str) -> u32 {
+    input
+        .lines()
+        .map(Room::parse)
+        .filter(Room::is_real)
+        .find(|r| r.decrypt() == NORTH_POLE_STORAGE)
+        .expect("the room where North Pole objects are stored")
+        .sector
+}
+
+/// An encrypted room name, as listed at the information kiosk
+#[derive(Debug)]
+struct Room<'a> {
+    name: &'a str,
+    sector: u32,
+    checksum: &'a str,
+}
+
+impl<'a> Room<'a> {
+    /// Parse `aaaaa-bbb-z-y-x-123[abxyz]`
+    fn parse(line: &'a str) -> Self {
+        let (rest, checksum) = line
+            .trim_end_matches(']')
+            .split_once('[')
+            .expect("checksum");
+        let (name, sector) = rest.rsplit_once('-').expect("sector ID");
+
+        Self {
+            name,
+            sector: sector.parse().expect("numeric sector ID"),
+            checksum,
+        }
+    }
+
+    /// Whether the checksum lists the five most common letters, ties broken alphabetically
+    fn is_real(&self) -> bool {
+        let mut counts = [0usize; 26];
+        for b in self.name.bytes().filter(u8::is_ascii_lowercase) {
+            counts[(b - b'a') as usize] += 1;
+        }
+
+        let mut letters: Vec<u8> = (b'a'..=b'z').collect();
+        // stable, so equally common letters stay in alphabetical order
+        letters.sort_by_key(|&l| std::cmp::Reverse(counts[(l - b'a') as usize]));
+        letters[..5] == *self.checksum.as_bytes()
+    }
+
+    /// Rotate every letter forward by the sector ID, dashes become spaces
+    fn decrypt(&self) -> String {
+        let shift = (self.sector % 26) as u8;
+        self.name
+            .bytes()
+            .map(|b| match b {
+                b'-' => ' ',
+                _ => ((b - b'a' + shift) % 26 + b'a') as char,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(1514, day04p1(INPUT));
+    }
+
+    #[test]
+    fn part2_examples() {
+        assert_eq!(
+            "very encrypted name",
+            Room::parse("qzmt-zixmtkozy-ivhz-343[zimth]").decrypt()
+        );
+        assert_eq!(
+            343,
+            day04p2(&format!("{INPUT}ijmockjgz-jwezxo-nojmvbz-343[jozmb]\n"))
+        );
+    }
+
+    const INPUT: &str = "aaaaa-bbb-z-y-x-123[abxyz]
+a-b-c-d-e-f-g-h-987[abcde]
+not-a-real-room-404[oarel]
+totally-real-room-200[decoy]
+";
+}
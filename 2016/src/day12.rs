@@ -0,0 +1,39 @@
+use crate::assembunny::{Machine, Word};
+
+pub fn day12p1(input: &str) -> Word {
+    let mut m = Machine::parse(input);
+    m.run();
+    m.reg('a')
+}
+
+pub fn day12p2(input: &str) -> Word {
+    let mut m = Machine::parse(input).with_register('c', 1);
+    m.run();
+    m.reg('a')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(42, day12p1(INPUT));
+    }
+
+    #[test]
+    fn part2_examples() {
+        assert_eq!(42, day12p2(INPUT));
+
+        // an addition loop that depends on `c`
+        assert_eq!(5, day12p2("cpy 4 a\ninc a\ndec c\njnz c -2\n"));
+    }
+
+    const INPUT: &str = "cpy 41 a
+inc a
+inc a
+dec a
+jnz a 2
+dec a
+";
+}
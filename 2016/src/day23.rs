@@ -0,0 +1,63 @@
+use crate::assembunny::{Machine, Word};
+
+pub fn day23p1(input: &str) -> Word {
+    safe_value(input, 7)
+}
+
+pub fn day23p2(input: &str) -> Word {
+    safe_value(input, 12)
+}
+
+/// The value sent to the safe when `eggs` is entered into the keypad.
+fn safe_value(program: &str, eggs: Word) -> Word {
+    let mut m = Machine::parse(program).with_register('a', eggs);
+    m.run();
+    m.reg('a')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(3, day23p1(INPUT));
+    }
+
+    #[test]
+    fn part2_examples() {
+        // computes a! with a multiplication loop, then toggles the final jump away
+        assert_eq!(479001600, day23p2(FACTORIAL));
+        assert_eq!(5040, day23p1(FACTORIAL));
+    }
+
+    const INPUT: &str = "cpy 2 a
+tgl a
+tgl a
+tgl a
+cpy 1 a
+dec a
+dec a
+";
+
+    const FACTORIAL: &str = "cpy a b
+dec b
+cpy a d
+cpy 0 a
+cpy b c
+inc a
+dec c
+jnz c -2
+dec d
+jnz d -5
+dec b
+cpy b c
+cpy c d
+dec d
+inc c
+jnz d -2
+tgl c
+cpy -16 c
+jnz 1 c
+";
+}
@@ -0,0 +1,48 @@
+use crate::assembunny::{Machine, Word};
+
+/// Number of outputs to check; real programs repeat their signal long before that.
+const SIGNAL_LENGTH: usize = 100;
+
+pub fn day25p1(input: &str) -> Word {
+    let program = Machine::parse(input);
+    (1..)
+        .find(|&a| is_clock_signal(program.clone().with_register('a', a)))
+        .unwrap()
+}
+
+pub fn day25p2(_input: &str) -> u32 {
+    // the last star comes for free
+    0
+}
+
+/// Whether the machine outputs `0, 1, 0, 1, ...`
+fn is_clock_signal(mut m: Machine) -> bool {
+    (0..SIGNAL_LENGTH).all(|i| m.next_output() == Some(i as Word % 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(3, day25p1(INPUT));
+    }
+
+    #[test]
+    fn part2_examples() {
+        assert_eq!(0, day25p2(INPUT));
+    }
+
+    // a clock signal for a = 3, a single 1 otherwise
+    const INPUT: &str = "cpy a b
+dec b
+dec b
+dec b
+jnz b 4
+out 0
+out 1
+jnz 1 -2
+out 1
+";
+}
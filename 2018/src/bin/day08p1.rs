@@ -0,0 +1,6 @@
+use advent_of_code_2018::day08::day08p1;
+
+fn main() {
+    let input = include_str!("../../input/day08.txt");
+    dbg!(day08p1(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2018::day14::day14p1;
+
+fn main() {
+    let input = include_str!("../../input/day14.txt");
+    dbg!(day14p1(input));
+}
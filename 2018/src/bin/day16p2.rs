@@ -0,0 +1,6 @@
+use advent_of_code_2018::day16::day16p2;
+
+fn main() {
+    let input = include_str!("../../input/day16.txt");
+    dbg!(day16p2(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2018::day17::day17p2;
+
+fn main() {
+    let input = include_str!("../../input/day17.txt");
+    dbg!(day17p2(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2018::day18::day18p1;
+
+fn main() {
+    let input = include_str!("../../input/day18.txt");
+    dbg!(day18p1(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2018::day21::day21p2;
+
+fn main() {
+    let input = include_str!("../../input/day21.txt");
+    dbg!(day21p2(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2018::day22::day22p2;
+
+fn main() {
+    let input = include_str!("../../input/day22.txt");
+    dbg!(day22p2(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2018::day24::day24p2;
+
+fn main() {
+    let input = include_str!("../../input/day24.txt");
+    dbg!(day24p2(input));
+}
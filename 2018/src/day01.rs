@@ -1,23 +1,19 @@
 use std::collections::HashSet;
 
-#[aoc_generator(day1)]
-fn generator(input: &str) -> Vec<i32> {
-    input.lines().map(|l| l.parse::<i32>().unwrap()).collect()
-}
+use common::generate::ints;
 
-#[aoc(day1, part1)]
-pub fn part1(input: &[i32]) -> i32 {
-    input.iter().sum()
+pub fn day01p1(input: &str) -> i32 {
+    ints::<i32>(input).into_iter().sum()
 }
 
-#[aoc(day1, part2)]
-pub fn part2(input: &[i32]) -> i32 {
+pub fn day01p2(input: &str) -> i32 {
+    let changes = ints::<i32>(input);
     let mut seen = HashSet::new();
     let mut sum = 0;
     seen.insert(sum);
 
     loop {
-        for i in input {
+        for i in &changes {
             sum += i;
             if !seen.insert(sum) {
                 return sum;
@@ -32,18 +28,18 @@ mod tests {
 
     #[test]
     fn part1_examples() {
-        assert_eq!(3, part1(&[1, -2, 3, 1]));
-        assert_eq!(3, part1(&[1, 1, 1]));
-        assert_eq!(0, part1(&[1, 1, -2]));
-        assert_eq!(-6, part1(&[-1, -2, -3]));
+        assert_eq!(3, day01p1("+1\n-2\n+3\n+1\n"));
+        assert_eq!(3, day01p1("+1\n+1\n+1\n"));
+        assert_eq!(0, day01p1("+1\n+1\n-2\n"));
+        assert_eq!(-6, day01p1("-1\n-2\n-3\n"));
     }
 
     #[test]
     fn part2_examples() {
-        assert_eq!(2, part2(&[1, -2, 3, 1]));
-        assert_eq!(0, part2(&[1, -1]));
-        assert_eq!(10, part2(&[3, 3, 4, -2, -4]));
-        assert_eq!(5, part2(&[-6, 3, 8, 5, -6]));
-        assert_eq!(14, part2(&[7, 7, -2, -7, -4]));
+        assert_eq!(2, day01p2("+1\n-2\n+3\n+1\n"));
+        assert_eq!(0, day01p2("+1\n-1\n"));
+        assert_eq!(10, day01p2("+3\n+3\n+4\n-2\n-4\n"));
+        assert_eq!(5, day01p2("-6\n+3\n+8\n+5\n-6\n"));
+        assert_eq!(14, day01p2("+7\n+7\n-2\n-7\n-4\n"));
     }
 }
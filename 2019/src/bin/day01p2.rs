@@ -0,0 +1,6 @@
+use advent_of_code_2019::day01::day01p2;
+
+fn main() {
+    let input = include_str!("../../input/day01.txt");
+    dbg!(day01p2(input));
+}
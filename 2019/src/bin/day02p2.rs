@@ -0,0 +1,6 @@
+use advent_of_code_2019::day02::day02p2;
+
+fn main() {
+    let input = include_str!("../../input/day02.txt");
+    dbg!(day02p2(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2019::day03::day03p1;
+
+fn main() {
+    let input = include_str!("../../input/day03.txt");
+    dbg!(day03p1(input));
+}
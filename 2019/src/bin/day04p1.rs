@@ -0,0 +1,6 @@
+use advent_of_code_2019::day04::day04p1;
+
+fn main() {
+    let input = include_str!("../../input/day04.txt");
+    dbg!(day04p1(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2019::day09::day09p1;
+
+fn main() {
+    let input = include_str!("../../input/day09.txt");
+    dbg!(day09p1(input));
+}
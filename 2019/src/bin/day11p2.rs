@@ -0,0 +1,6 @@
+use advent_of_code_2019::day11::day11p2;
+
+fn main() {
+    let input = include_str!("../../input/day11.txt");
+    dbg!(day11p2(input));
+}
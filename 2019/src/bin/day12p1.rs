@@ -0,0 +1,6 @@
+use advent_of_code_2019::day12::day12p1;
+
+fn main() {
+    let input = include_str!("../../input/day12.txt");
+    dbg!(day12p1(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2019::day13::day13p2;
+
+fn main() {
+    let input = include_str!("../../input/day13.txt");
+    dbg!(day13p2(input));
+}
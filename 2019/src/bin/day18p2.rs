@@ -0,0 +1,6 @@
+use advent_of_code_2019::day18::day18p2;
+
+fn main() {
+    let input = include_str!("../../input/day18.txt");
+    dbg!(day18p2(input));
+}
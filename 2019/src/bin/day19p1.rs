@@ -0,0 +1,6 @@
+use advent_of_code_2019::day19::day19p1;
+
+fn main() {
+    let input = include_str!("../../input/day19.txt");
+    dbg!(day19p1(input));
+}
@@ -0,0 +1,6 @@
+use advent_of_code_2019::day23::day23p2;
+
+fn main() {
+    let input = include_str!("../../input/day23.txt");
+    dbg!(day23p2(input));
+}
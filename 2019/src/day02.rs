@@ -0,0 +1,51 @@
+use crate::intcode::{Machine, Word};
+
+const TARGET: Word = 19690720;
+
+pub fn day02p1(input: &str) -> Word {
+    run(input, 12, 2)
+}
+
+pub fn day02p2(input: &str) -> Word {
+    let (noun, verb) = find_inputs(input, TARGET).expect("noun and verb");
+    100 * noun + verb
+}
+
+/// Run the program with `noun` and `verb` in addresses 1 and 2, returning the value at 0.
+fn run(program: &str, noun: Word, verb: Word) -> Word {
+    let mut m = Machine::parse(program);
+    m.poke(1, noun);
+    m.poke(2, verb);
+    m.run();
+    m.peek(0)
+}
+
+fn find_inputs(program: &str, target: Word) -> Option<(Word, Word)> {
+    (0..100)
+        .flat_map(|noun| (0..100).map(move |verb| (noun, verb)))
+        .find(|&(noun, verb)| run(program, noun, verb) == target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(3500, run(INPUT, 9, 10));
+
+        let mut m = Machine::parse("1,1,1,4,99,5,6,0,99");
+        m.run();
+        assert_eq!(30, m.peek(0));
+    }
+
+    #[test]
+    fn part2_examples() {
+        // several inputs produce 3500 here, any of them will do
+        let (noun, verb) = find_inputs(INPUT, 3500).unwrap();
+        assert_eq!(3500, run(INPUT, noun, verb));
+        assert_eq!(None, find_inputs(INPUT, 3501));
+    }
+
+    const INPUT: &str = "1,9,10,3,2,3,11,0,99,30,40,50";
+}
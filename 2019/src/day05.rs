@@ -0,0 +1,64 @@
+use crate::intcode::{Machine, Word};
+
+pub fn day05p1(input: &str) -> Word {
+    diagnostic(input, 1)
+}
+
+pub fn day05p2(input: &str) -> Word {
+    diagnostic(input, 5)
+}
+
+/// Run the diagnostic program for the system with the given ID.
+///
+/// All outputs but the last one are test results, which must be 0.
+fn diagnostic(program: &str, system: Word) -> Word {
+    let output = Machine::parse(program).run_with([system]);
+    let (&code, tests) = output.split_last().expect("a diagnostic code");
+    assert!(tests.iter().all(|&t| t == 0), "failed tests: {tests:?}");
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_examples() {
+        // echo
+        assert_eq!(vec![42], Machine::parse("3,0,4,0,99").run_with([42]));
+
+        // parameter modes and negative values
+        let mut m = Machine::parse("1002,4,3,4,33");
+        m.run();
+        assert_eq!(99, m.peek(4));
+        let mut m = Machine::parse("1101,100,-1,4,0");
+        m.run();
+        assert_eq!(99, m.peek(4));
+    }
+
+    #[test]
+    fn part2_examples() {
+        let cases = [
+            ("3,9,8,9,10,9,4,9,99,-1,8", [0, 1, 0]),
+            ("3,9,7,9,10,9,4,9,99,-1,8", [1, 0, 0]),
+            ("3,3,1108,-1,8,3,4,3,99", [0, 1, 0]),
+            ("3,3,1107,-1,8,3,4,3,99", [1, 0, 0]),
+            ("3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9", [1, 1, 1]),
+            ("3,3,1105,-1,9,1101,0,0,12,4,12,99,1", [1, 1, 1]),
+            (INPUT, [999, 1000, 1001]),
+        ];
+        for (program, expected) in cases {
+            for (input, expected) in [7, 8, 9].into_iter().zip(expected) {
+                assert_eq!(
+                    expected,
+                    diagnostic(program, input),
+                    "{program} with {input}"
+                );
+            }
+        }
+    }
+
+    const INPUT: &str = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,
+1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,
+999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
+}
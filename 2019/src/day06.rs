@@ -0,0 +1,24 @@
+pub fn day06p1(_input: &str) -> u32 {
+    0
+}
+
+pub fn day06p2(_input: &str) -> u32 {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(0, day06p1(INPUT));
+    }
+
+    #[test]
+    fn part2_examples() {
+        assert_eq!(0, day06p2(INPUT));
+    }
+
+    const INPUT: &str = "";
+}
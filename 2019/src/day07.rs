@@ -0,0 +1,54 @@
+use common::combinatorics::permutations;
+
+use crate::intcode::{feedback_loop, Machine, Word};
+
+pub fn day07p1(input: &str) -> Word {
+    max_signal(input, [0, 1, 2, 3, 4])
+}
+
+pub fn day07p2(input: &str) -> Word {
+    max_signal(input, [5, 6, 7, 8, 9])
+}
+
+/// The highest signal the amplifiers can send to the thrusters, trying every order of `phases`.
+fn max_signal(program: &str, phases: [Word; 5]) -> Word {
+    let program = Machine::parse(program);
+    permutations(&phases)
+        .map(|order| {
+            let mut amps: Vec<_> = order
+                .iter()
+                .map(|&phase| program.clone().with_input([phase]))
+                .collect();
+            feedback_loop(&mut amps, 0).expect("a signal")
+        })
+        .max()
+        .expect("at least one phase setting")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(
+            43210,
+            day07p1("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0")
+        );
+        assert_eq!(
+            54321,
+            day07p1("3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0")
+        );
+    }
+
+    #[test]
+    fn part2_examples() {
+        assert_eq!(
+            139629729,
+            day07p2(
+                "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,\
+                 99,0,0,5"
+            )
+        );
+    }
+}
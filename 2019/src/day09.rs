@@ -0,0 +1,41 @@
+use crate::intcode::{Machine, Word};
+
+pub fn day09p1(input: &str) -> Word {
+    boost(input, 1)
+}
+
+pub fn day09p2(input: &str) -> Word {
+    boost(input, 2)
+}
+
+fn boost(program: &str, mode: Word) -> Word {
+    let output = Machine::parse(program).run_with([mode]);
+    match output[..] {
+        [keycode] => keycode,
+        _ => panic!("malfunctioning opcodes: {output:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_examples() {
+        const QUINE: &str = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+        let expected: Vec<Word> = QUINE.split(',').map(|v| v.parse().unwrap()).collect();
+        assert_eq!(expected, Machine::parse(QUINE).run_with([]));
+
+        let output = Machine::parse("1102,34915192,34915192,7,4,7,99,0").run_with([]);
+        assert_eq!(16, output[0].to_string().len());
+
+        let output = Machine::parse("104,1125899906842624,99").run_with([]);
+        assert_eq!(vec![1125899906842624], output);
+    }
+
+    #[test]
+    fn part2_examples() {
+        // relative mode writes, far beyond the program
+        assert_eq!(7, boost("109,1000,203,5000,204,5000,99", 7));
+    }
+}
@@ -0,0 +1,382 @@
+//! The Intcode computer, which many of this year's puzzles build on
+//!
+//! # Examples
+//!
+//! ```
+//! use advent_of_code_2019::intcode::{Machine, State};
+//!
+//! // outputs 1 if the input equals 8, 0 otherwise
+//! let mut m = Machine::parse("3,9,8,9,10,9,4,9,99,-1,8");
+//! m.push_input(8);
+//! assert_eq!(State::Halted, m.run());
+//! assert_eq!(Some(1), m.pop_output());
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+
+/// The values Intcode programs work with
+pub type Word = i64;
+
+/// Why a machine stopped running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    /// Executed opcode 99
+    Halted,
+    /// Needs input before it can continue
+    WaitingForInput,
+}
+
+/// Memory that grows on demand
+///
+/// The program lives in a dense vector; writes beyond it go into a map, so programs can use
+/// addresses far beyond their own length without allocating everything in between. Unwritten
+/// addresses read as 0.
+#[derive(Debug, Clone, Default)]
+struct Memory {
+    dense: Vec<Word>,
+    sparse: HashMap<usize, Word>,
+}
+
+impl Memory {
+    fn get(&self, addr: usize) -> Word {
+        match self.dense.get(addr) {
+            Some(&v) => v,
+            None => self.sparse.get(&addr).copied().unwrap_or(0),
+        }
+    }
+
+    fn set(&mut self, addr: usize, value: Word) {
+        match self.dense.get_mut(addr) {
+            Some(v) => *v = value,
+            None => {
+                self.sparse.insert(addr, value);
+            }
+        }
+    }
+}
+
+/// An Intcode computer with its program, input and output queues
+#[derive(Debug, Clone)]
+pub struct Machine {
+    memory: Memory,
+    ip: usize,
+    relative_base: Word,
+    input: VecDeque<Word>,
+    output: VecDeque<Word>,
+    halted: bool,
+}
+
+impl Machine {
+    pub fn new(program: Vec<Word>) -> Self {
+        Self {
+            memory: Memory {
+                dense: program,
+                sparse: HashMap::new(),
+            },
+            ip: 0,
+            relative_base: 0,
+            input: VecDeque::new(),
+            output: VecDeque::new(),
+            halted: false,
+        }
+    }
+
+    /// Parse a comma separated program
+    pub fn parse(program: &str) -> Self {
+        Self::new(
+            program
+                .trim()
+                .split(',')
+                .map(|v| v.trim().parse().expect("intcode value"))
+                .collect(),
+        )
+    }
+
+    /// Queue up input values
+    pub fn with_input(mut self, input: impl IntoIterator<Item = Word>) -> Self {
+        self.input.extend(input);
+        self
+    }
+
+    pub fn push_input(&mut self, value: Word) {
+        self.input.push_back(value);
+    }
+
+    /// Feed a line of text as ASCII codes, followed by a newline
+    pub fn push_line(&mut self, line: &str) {
+        self.input
+            .extend(line.bytes().chain([b'\n']).map(Word::from));
+    }
+
+    /// The oldest output value not yet taken
+    pub fn pop_output(&mut self) -> Option<Word> {
+        self.output.pop_front()
+    }
+
+    /// All pending output values
+    pub fn take_output(&mut self) -> Vec<Word> {
+        self.output.drain(..).collect()
+    }
+
+    /// Whether the program has executed opcode 99
+    pub fn halted(&self) -> bool {
+        self.halted
+    }
+
+    /// The value at `addr`
+    pub fn peek(&self, addr: usize) -> Word {
+        self.memory.get(addr)
+    }
+
+    /// Overwrite the value at `addr`
+    pub fn poke(&mut self, addr: usize, value: Word) {
+        self.memory.set(addr, value);
+    }
+
+    /// Run until the program halts, or needs input that is not there yet
+    ///
+    /// A waiting machine continues where it left off once input has been pushed and it is run
+    /// again.
+    pub fn run(&mut self) -> State {
+        loop {
+            if let Some(state) = self.step() {
+                return state;
+            }
+        }
+    }
+
+    /// Push `input`, run, and return all output
+    pub fn run_with(&mut self, input: impl IntoIterator<Item = Word>) -> Vec<Word> {
+        self.input.extend(input);
+        self.run();
+        self.take_output()
+    }
+
+    /// Execute a single instruction; returns the state if the machine can not continue
+    pub fn step(&mut self) -> Option<State> {
+        if self.halted {
+            return Some(State::Halted);
+        }
+
+        let instruction = self.memory.get(self.ip);
+        let opcode = instruction % 100;
+        match opcode {
+            1 | 2 | 7 | 8 => {
+                let (a, b) = (self.read(1), self.read(2));
+                let value = match opcode {
+                    1 => a + b,
+                    2 => a * b,
+                    7 => (a < b).into(),
+                    _ => (a == b).into(),
+                };
+                self.write(3, value);
+                self.ip += 4;
+            }
+            3 => {
+                let Some(value) = self.input.pop_front() else {
+                    return Some(State::WaitingForInput);
+                };
+                self.write(1, value);
+                self.ip += 2;
+            }
+            4 => {
+                let value = self.read(1);
+                self.output.push_back(value);
+                self.ip += 2;
+            }
+            5 | 6 => {
+                let (cond, target) = (self.read(1), self.read(2));
+                if (cond != 0) == (opcode == 5) {
+                    self.ip = address(target);
+                } else {
+                    self.ip += 3;
+                }
+            }
+            9 => {
+                self.relative_base += self.read(1);
+                self.ip += 2;
+            }
+            99 => {
+                self.halted = true;
+                return Some(State::Halted);
+            }
+            _ => panic!("invalid opcode {instruction} at {}", self.ip),
+        }
+
+        None
+    }
+
+    /// Whether the next instruction reads input, and there is none
+    fn waiting(&self) -> bool {
+        !self.halted && self.memory.get(self.ip) % 100 == 3 && self.input.is_empty()
+    }
+
+    fn mode(&self, param: usize) -> Word {
+        self.memory.get(self.ip) / 10i64.pow(param as u32 + 1) % 10
+    }
+
+    /// The address parameter `param` refers to
+    fn param_address(&self, param: usize) -> usize {
+        let raw = self.memory.get(self.ip + param);
+        match self.mode(param) {
+            0 => address(raw),
+            2 => address(self.relative_base + raw),
+            m => panic!("invalid parameter mode {m} at {}", self.ip),
+        }
+    }
+
+    fn read(&self, param: usize) -> Word {
+        match self.mode(param) {
+            1 => self.memory.get(self.ip + param),
+            _ => self.memory.get(self.param_address(param)),
+        }
+    }
+
+    fn write(&mut self, param: usize, value: Word) {
+        let addr = self.param_address(param);
+        self.memory.set(addr, value);
+    }
+}
+
+fn address(value: Word) -> usize {
+    usize::try_from(value).unwrap_or_else(|_| panic!("negative address {value}"))
+}
+
+/// Connect machines in a circle, each one's output feeding the next one's input
+///
+/// `input` is given to the first machine, and the machines are run in turn until all of them
+/// have halted. Returns the last value the final machine produced, or `None` if the machines
+/// deadlock, all waiting for input that never comes.
+///
+/// # Examples
+///
+/// ```
+/// use advent_of_code_2019::intcode::{feedback_loop, Machine};
+///
+/// // 2019 day 7: five amplifiers in series, each configured with a phase setting
+/// let program = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0";
+/// let mut amps: Vec<_> = [4, 3, 2, 1, 0]
+///     .into_iter()
+///     .map(|phase| Machine::parse(program).with_input([phase]))
+///     .collect();
+/// assert_eq!(Some(43210), feedback_loop(&mut amps, 0));
+///
+/// // reads two values but never writes any
+/// let mut stuck = [Machine::parse("3,0,3,0,99")];
+/// assert_eq!(None, feedback_loop(&mut stuck, 0));
+/// ```
+pub fn feedback_loop(machines: &mut [Machine], input: Word) -> Option<Word> {
+    let n = machines.len();
+    machines.first_mut()?.push_input(input);
+    let mut last = None;
+
+    while !machines.iter().all(Machine::halted) {
+        // without output or a newly halted machine, the next round would look exactly the same
+        let mut progress = false;
+        for i in 0..n {
+            let was_halted = machines[i].halted();
+            machines[i].run();
+            let output = machines[i].take_output();
+            progress |= !output.is_empty() || machines[i].halted() != was_halted;
+            if i == n - 1 {
+                last = output.last().copied().or(last);
+            }
+            machines[(i + 1) % n].input.extend(output);
+        }
+        if !progress {
+            return None;
+        }
+    }
+
+    last
+}
+
+/// A packet sent through a [`Network`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Packet {
+    pub dest: usize,
+    pub x: Word,
+    pub y: Word,
+}
+
+/// Machines running the same program, sending each other packets
+///
+/// Every machine first gets its address as input. It sends a packet by writing the destination
+/// address followed by the two values, and reads `-1` while it has no incoming packets.
+///
+/// # Examples
+///
+/// ```
+/// use advent_of_code_2019::intcode::{Network, Packet};
+///
+/// // every machine sends its own address to 255 twice, then halts
+/// let mut net = Network::new("3,100,104,255,4,100,4,100,99", 3);
+/// let outside = net.step();
+/// assert_eq!(3, outside.len());
+/// assert_eq!(Packet { dest: 255, x: 2, y: 2 }, outside[2]);
+/// assert!(!net.idle());
+///
+/// assert!(net.step().is_empty());
+/// assert!(net.idle());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Network {
+    machines: Vec<Machine>,
+    sent: usize,
+}
+
+impl Network {
+    pub fn new(program: &str, size: usize) -> Self {
+        let program = Machine::parse(program);
+        let machines = (0..size)
+            .map(|addr| program.clone().with_input([addr as Word]))
+            .collect();
+        Self { machines, sent: 0 }
+    }
+
+    /// Deliver a packet from outside the network
+    pub fn send(&mut self, packet: Packet) {
+        self.machines[packet.dest]
+            .input
+            .extend([packet.x, packet.y]);
+    }
+
+    /// Run every machine until it waits for input again, delivering packets between them
+    ///
+    /// Returns the packets addressed to machines outside the network.
+    pub fn step(&mut self) -> Vec<Packet> {
+        let mut outside = Vec::new();
+        self.sent = 0;
+
+        for i in 0..self.machines.len() {
+            if self.machines[i].waiting() {
+                self.machines[i].push_input(-1);
+            }
+            self.machines[i].run();
+
+            // a packet is only sent once all three values have been written
+            let mut output = self.machines[i].take_output();
+            let partial = output.split_off(output.len() / 3 * 3);
+            self.machines[i].output.extend(partial);
+            for chunk in output.chunks_exact(3) {
+                let packet = Packet {
+                    dest: address(chunk[0]),
+                    x: chunk[1],
+                    y: chunk[2],
+                };
+                self.sent += 1;
+                match self.machines.get_mut(packet.dest) {
+                    Some(m) => m.input.extend([packet.x, packet.y]),
+                    None => outside.push(packet),
+                }
+            }
+        }
+
+        outside
+    }
+
+    /// Whether no packets were sent in the last step, and none are waiting to be read
+    pub fn idle(&self) -> bool {
+        self.sent == 0 && self.machines.iter().all(|m| m.input.is_empty())
+    }
+}
@@ -1,78 +1,83 @@
-use std::collections::HashMap;
+use common::{bitset::BitSet64, interner::LabeledGraph, memo::Memo};
 
-#[derive(Debug)]
-struct Cave {
-    id: String,
-    small: bool,
-    links: Vec<String>,
+struct CaveSystem {
+    graph: LabeledGraph<u8>,
+    small: BitSet64,
+    start: u8,
+    end: u8,
 }
 
-impl From<String> for Cave {
-    fn from(id: String) -> Self {
-        let small = id == id.to_lowercase();
-        Self::new(id, small)
+impl From<&str> for CaveSystem {
+    fn from(input: &str) -> Self {
+        let graph = LabeledGraph::parse(input);
+        let small = graph
+            .nodes()
+            .filter(|&id| graph.label(id).chars().all(|c| c.is_ascii_lowercase()))
+            .map(usize::from)
+            .collect();
+        let start = graph.id("start").expect("start cave");
+        let end = graph.id("end").expect("end cave");
+
+        Self {
+            graph,
+            small,
+            start,
+            end,
+        }
     }
 }
 
-impl Cave {
-    fn new(id: String, small: bool) -> Self {
-        let links = Vec::new();
-        Self { id, small, links }
-    }
-
+impl CaveSystem {
     /// visits all the linked caves in order, and returns how many times the "end" cave has been reached.
+    ///
+    /// Different paths often reach the same cave having visited the same small caves, so the
+    /// counts are cached by that state.
     fn visit(
         &self,
-        system: &HashMap<String, Cave>,
-        path: &mut Vec<String>,
+        memo: &mut Visits,
+        cave: u8,
+        visited: BitSet64,
         can_visit_twice: bool,
     ) -> usize {
-        if self.small && !can_visit_twice && path.contains(&self.id) {
-            // already been here
-            return 0;
-        }
-
-        let can_visit_twice = can_visit_twice && (!self.small || !path.contains(&self.id));
-        path.push(self.id.clone());
-        let c = self
-            .links
-            .iter()
-            .map(|k| match k.as_str() {
-                "start" => 0,
-                "end" => 1,
-                _ => {
-                    let cave = system.get(k).unwrap();
-                    cave.visit(system, path, can_visit_twice)
-                }
-            })
-            .sum();
-        path.pop();
-
-        c
+        let visited = if self.small.contains(cave.into()) {
+            visited.with(cave.into())
+        } else {
+            visited
+        };
+
+        memo.get_or_compute((cave, visited, can_visit_twice), |memo| {
+            self.graph
+                .neighbors(cave)
+                .iter()
+                .map(|&next| {
+                    if next == self.start {
+                        0
+                    } else if next == self.end {
+                        1
+                    } else if !visited.contains(next.into()) {
+                        self.visit(memo, next, visited, can_visit_twice)
+                    } else if can_visit_twice {
+                        // already been here, but we may spend our one revisit on it
+                        self.visit(memo, next, visited, false)
+                    } else {
+                        0
+                    }
+                })
+                .sum()
+        })
     }
 }
 
-fn day12(input: &str, can_visit_twice: bool) -> usize {
-    let mut system: HashMap<String, Cave> = HashMap::new();
-    for line in input.lines() {
-        let parts: Vec<&str> = line.split('-').collect();
-
-        system
-            .entry(parts[0].to_string())
-            .or_insert_with_key(|k| Cave::from(k.clone()))
-            .links
-            .push(parts[1].to_string());
+type Visits = Memo<(u8, BitSet64, bool), usize>;
 
-        system
-            .entry(parts[1].to_string())
-            .or_insert_with_key(|k| Cave::from(k.clone()))
-            .links
-            .push(parts[0].to_string());
-    }
-
-    let mut path = Vec::new();
-    let start = system.get("start").unwrap();
-    start.visit(&system, &mut path, can_visit_twice)
+fn day12(input: &str, can_visit_twice: bool) -> usize {
+    let system = CaveSystem::from(input);
+    system.visit(
+        &mut Memo::new(),
+        system.start,
+        BitSet64::new(),
+        can_visit_twice,
+    )
 }
 
 pub fn day12p1(input: &str) -> usize {
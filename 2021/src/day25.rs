@@ -1,5 +1,32 @@
-pub fn day25p1(_input: &str) -> usize {
-    0
+use common::{
+    automaton::{Automaton, Neighborhood},
+    Grid,
+};
+
+pub fn day25p1(input: &str) -> usize {
+    let grid = Grid::parse(input, |c| c);
+    let mut seafloor = Automaton::new(grid)
+        .with_neighborhood(Neighborhood::VonNeumann)
+        .with_wrapping();
+
+    // neighbours come as [up, left, right, down]
+    let east = |&c: &char, n: &[char]| match c {
+        '.' if n[1] == '>' => '>',
+        '>' if n[2] == '.' => '.',
+        c => c,
+    };
+    let south = |&c: &char, n: &[char]| match c {
+        '.' if n[0] == 'v' => 'v',
+        'v' if n[3] == '.' => '.',
+        c => c,
+    };
+
+    // `|` rather than `||`, both herds have to move in every step
+    let mut steps = 1;
+    while seafloor.step(east) | seafloor.step(south) {
+        steps += 1;
+    }
+    steps
 }
 
 pub fn day25p2(_input: &str) -> usize {
@@ -12,7 +39,7 @@ mod tests {
 
     #[test]
     fn part1_examples() {
-        assert_eq!(0, day25p1(INPUT));
+        assert_eq!(58, day25p1(INPUT));
     }
 
     #[test]
@@ -20,5 +47,13 @@ mod tests {
         assert_eq!(0, day25p2(INPUT));
     }
 
-    const INPUT: &str = "";
+    const INPUT: &str = "v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>";
 }
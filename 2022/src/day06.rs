@@ -1,15 +1,15 @@
-use std::collections::HashSet;
+use common::bitset::BitSet128;
 
 #[inline]
 fn day06(input: &str, window_size: usize) -> usize {
     input
-        .chars()
-        .collect::<Vec<_>>()
+        .as_bytes()
         .windows(window_size)
         .enumerate()
         .find_map(|(i, w)| {
             w.iter()
-                .collect::<HashSet<_>>()
+                .map(|&c| usize::from(c))
+                .collect::<BitSet128>()
                 .len()
                 .eq(&window_size)
                 .then_some(i + window_size)
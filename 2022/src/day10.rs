@@ -1,145 +1,84 @@
-use crt::*;
+use std::fmt;
 
-mod crt {
-    use log::debug;
+use common::vm::{Cpu, Flow, InstructionSet, Machine};
 
-    /// An instruction from the input, either `noop` or `addx N`
-    #[derive(Debug)]
-    pub enum Instruction {
-        Noop,
-        AddX(i64),
-    }
-    impl Instruction {
-        /// Parse a line and return an instruction from it
-        ///
-        /// Panics on invalid input.
-        pub fn parse(i: &str) -> Self {
-            let words = i.split_ascii_whitespace().collect::<Vec<_>>();
-            match words.as_slice() {
-                ["noop"] => Self::Noop,
-                ["addx", n] => Self::AddX(n.parse().unwrap()),
-                _ => unreachable!("Invalid input: {i}"),
-            }
-        }
-
-        /// Determine the number of cycles an instruction takes
-        pub fn cycles(&self) -> u64 {
-            match self {
-                Self::Noop => 1,
-                Self::AddX(_) => 2,
-            }
-        }
-    }
-
-    /// Models the internal state of our CPU
-    #[derive(Debug)]
-    pub struct Cpu<const CRT_W: usize, const CRT_H: usize> {
-        /// The current cycle we're on
-        cycle: usize,
+const CRT_W: usize = 40;
+const CRT_H: usize = 6;
 
-        /// The X register
-        x: i64,
-
-        /// Accumulator of all the samples
-        acc: i64,
-
-        /// when the next sample must be taken
-        ///
-        /// (in cycle 20 and every 40th after that)
-        next_sample: usize,
-
-        /// Pixels of the CRT
-        crt: [[bool; CRT_W]; CRT_H],
-    }
+/// An instruction from the input, either `noop` or `addx N`
+#[derive(Debug)]
+pub enum Instruction {
+    Noop,
+    AddX(i64),
+}
 
-    impl<const CRT_W: usize, const CRT_H: usize> Default for Cpu<CRT_W, CRT_H> {
-        /// Correctly initialize a CPU as per problem statement
-        ///
-        /// X is set to 1, and the next sample will be in cycle 20.
-        fn default() -> Self {
-            Self {
-                cycle: 0,
-                x: 1,
-                acc: 0,
-                next_sample: 20,
-                crt: [[false; CRT_W]; CRT_H],
-            }
+impl InstructionSet for Instruction {
+    type Word = i64;
+    const REGISTERS: &'static [&'static str] = &["x"];
+
+    /// Panics on invalid input.
+    fn parse(i: &str) -> Self {
+        let words = i.split_ascii_whitespace().collect::<Vec<_>>();
+        match words.as_slice() {
+            ["noop"] => Self::Noop,
+            ["addx", n] => Self::AddX(n.parse().unwrap()),
+            _ => unreachable!("Invalid input: {i}"),
         }
     }
 
-    impl<const CRT_W: usize, const CRT_H: usize> Cpu<CRT_W, CRT_H> {
-        /// Exectute the given instruction
-        pub fn execute(&mut self, i: Instruction) {
-            debug!("{self:?} {i:?}");
-
-            for _ in 0..i.cycles() {
-                // determine whether to draw the current pixel
-                let line = self.cycle / CRT_W;
-                let pos = self.cycle % CRT_W;
-                self.crt[line][pos] = self.x.abs_diff(pos.try_into().unwrap()) <= 1;
-
-                // Increase the cycle counter
-                self.cycle += 1;
-
-                // If we passed `next_sample`, we must take a sample
-                if self.cycle >= self.next_sample {
-                    // determine this cycle's signal strength
-                    let sig_str = self.next_sample as i64 * self.x;
-                    // Add together all measurements
-                    self.acc += sig_str;
-                    // determine when the next measurement must be
-                    self.next_sample += 40;
-                }
-            }
-
-            match i {
-                Instruction::Noop => {}
-                Instruction::AddX(n) => self.x += n,
-            }
+    fn execute(&self, cpu: &mut Cpu<i64>) -> Flow {
+        if let Self::AddX(n) = self {
+            cpu.regs[0] += n;
         }
+        Flow::Next
+    }
 
-        /// Return the current value of the `acc` register
-        pub fn get_acc(&self) -> i64 {
-            self.acc
+    fn cycles(&self) -> usize {
+        match self {
+            Self::Noop => 1,
+            Self::AddX(_) => 2,
         }
+    }
+}
 
-        /// Render the CRT into a string
-        pub fn render_crt(&self) -> String {
-            self.crt
-                .iter()
-                .map(|row| {
-                    row.iter()
-                        .map(|c| match c {
-                            true => '#',
-                            false => '.',
-                        })
-                        .collect()
-                })
-                .collect::<Vec<String>>()
-                .join("\n")
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Noop => write!(f, "noop"),
+            Self::AddX(n) => write!(f, "addx {n}"),
         }
     }
 }
 
-pub fn run(input: &str) -> Cpu<40, 6> {
-    let mut cpu = Cpu::default();
-
-    input
-        .lines()
-        .map(Instruction::parse)
-        .for_each(|i| cpu.execute(i));
-
-    cpu
+/// Run the program, calling `hook` with the cycle number and the value of X during every cycle
+fn run(input: &str, mut hook: impl FnMut(usize, i64)) {
+    Machine::<Instruction>::parse(input)
+        .with_register("x", 1)
+        .run_with(|cpu| hook(cpu.cycle(), cpu.regs[0]));
 }
 
 pub fn day10p1(input: &str) -> i64 {
-    let cpu = run(input);
-    cpu.get_acc()
+    // sample the signal strength in cycle 20 and every 40th after that
+    let mut acc = 0;
+    run(input, |cycle, x| {
+        if cycle % 40 == 20 {
+            acc += cycle as i64 * x;
+        }
+    });
+    acc
 }
 
 pub fn day10p2(input: &str) -> String {
-    let cpu = run(input);
-    cpu.render_crt()
+    let mut crt = [[false; CRT_W]; CRT_H];
+    run(input, |cycle, x| {
+        let (line, pos) = ((cycle - 1) / CRT_W, (cycle - 1) % CRT_W);
+        crt[line][pos] = x.abs_diff(pos as i64) <= 1;
+    });
+
+    crt.iter()
+        .map(|row| row.iter().map(|&c| if c { '#' } else { '.' }).collect())
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
 #[cfg(test)]
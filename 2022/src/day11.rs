@@ -1,5 +1,17 @@
 use std::{cmp::Reverse, ops::Div};
 
+use common::{
+    expr::Grammar,
+    math::lcm_all,
+    parse::{blocks, comma_list, field, finish_all, unsigned, IResult},
+};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, line_ending, not_line_ending},
+    combinator::map_res,
+    sequence::{preceded, terminated, tuple},
+};
+
 use monkey::*;
 
 mod monkey {
@@ -63,76 +75,33 @@ mod monkey {
 }
 
 fn parse_monkeys(input: &str) -> Vec<Monkey> {
-    input
-        .split("\n\n")
-        .map(|s| {
-            let mut lines = s.lines().skip(1);
-            let items = lines
-                .next()
-                .expect("starting items")
-                .split(": ")
-                .nth(1)
-                .expect("items")
-                .split(", ")
-                .map(|i| i.parse().expect("number"))
-                .collect();
-
-            let operation: Box<dyn Fn(Item) -> Item> = match lines
-                .next()
-                .expect("Operation")
-                .split(": ")
-                .nth(1)
-                .expect("operation description")
-                .split_ascii_whitespace()
-                .collect::<Vec<_>>()
-                .as_slice()
-            {
-                ["new", "=", "old", "*", "old"] => Box::new(move |i: Item| i.pow(2)),
-                ["new", "=", "old", "*", n] => {
-                    let n = n.parse::<Item>().expect("n to be a number");
-                    Box::new(move |i: Item| i * n)
-                }
-                ["new", "=", "old", "+", n] => {
-                    let n = n.parse::<Item>().expect("n to be a number");
-                    Box::new(move |i: Item| i + n)
-                }
-                op => panic!("Unknown operation: {op:?}"),
-            };
-
-            let divisor = match lines
-                .next()
-                .expect("Test")
-                .split(": ")
-                .nth(1)
-                .expect("test condition")
-                .split_ascii_whitespace()
-                .collect::<Vec<_>>()
-                .as_slice()
-            {
-                ["divisible", "by", n] => n.parse::<Item>().expect("n to be a number"),
-                t => panic!("Unknown test: {t:?}"),
-            };
-
-            let on_true = lines
-                .next()
-                .expect("If true")
-                .split_ascii_whitespace()
-                .last()
-                .expect("target monkey")
-                .parse()
-                .unwrap();
-            let on_false = lines
-                .next()
-                .expect("If false")
-                .split_ascii_whitespace()
-                .last()
-                .expect("target monkey")
-                .parse()
-                .unwrap();
-
-            Monkey::new(items, operation, divisor, on_true, on_false)
-        })
-        .collect::<Vec<_>>()
+    finish_all(blocks(parse_monkey), input).expect("valid monkeys")
+}
+
+fn parse_monkey(i: &str) -> IResult<&str, Monkey> {
+    let (i, _) = tuple((tag("Monkey "), unsigned::<usize>, char(':'), line_ending))(i)?;
+    let (i, items) = terminated(field("Starting items", comma_list(unsigned)), line_ending)(i)?;
+    let grammar = Grammar::<Item>::arithmetic();
+    let expr = map_res(not_line_ending, |s| grammar.parse(s));
+    let (i, operation) = terminated(
+        field("Operation", preceded(tag("new = "), expr)),
+        line_ending,
+    )(i)?;
+    let (i, divisor) = terminated(
+        field("Test", preceded(tag("divisible by "), unsigned)),
+        line_ending,
+    )(i)?;
+    let target = |outcome| field(outcome, preceded(tag("throw to monkey "), unsigned));
+    let (i, on_true) = terminated(target("If true"), line_ending)(i)?;
+    let (i, on_false) = target("If false")(i)?;
+
+    let operation = move |old: Item| {
+        operation
+            .eval(&mut |v| (v == "old").then_some(old))
+            .expect("only `old` in operation")
+    };
+
+    Ok((i, Monkey::new(items, operation, divisor, on_true, on_false)))
 }
 
 pub fn day11p1(input: &str) -> u64 {
@@ -161,7 +130,8 @@ pub fn day11p1(input: &str) -> u64 {
 pub fn day11p2(input: &str) -> u64 {
     let mut monkeys = parse_monkeys(input);
     let num_monkeys = monkeys.len();
-    let divisor_product = monkeys.iter().fold(1, |acc, m| acc * m.divisor);
+    let divisor_lcm =
+        lcm_all(monkeys.iter().map(|m| m.divisor)).expect("lcm of the divisors overflows");
 
     for r in 0..10_000 {
         if r % 1000 == 0 {
@@ -173,7 +143,7 @@ pub fn day11p2(input: &str) -> u64 {
                 let m = &mut monkeys[i];
 
                 m.inspections += 1;
-                let item = (m.operation)(item) % divisor_product;
+                let item = (m.operation)(item) % divisor_lcm;
                 let throw_to = m.throw_to(&item);
                 monkeys[throw_to].items.push(item);
             }
@@ -200,6 +170,14 @@ mod tests {
         assert_eq!(2713310158, day11p2(INPUT));
     }
 
+    #[test]
+    fn invalid_operation() {
+        let input = INPUT.replacen("old * 19", "old * * 19", 1);
+        let err = finish_all(blocks(parse_monkey), &input).unwrap_err();
+        // reported at the start of the expression
+        assert_eq!((3, 20), (err.line, err.column));
+    }
+
     const INPUT: &str = "Monkey 0:
   Starting items: 79, 98
   Operation: new = old * 19
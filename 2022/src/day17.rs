@@ -1,3 +1,4 @@
+use common::cycle::CycleDetector;
 use flow::*;
 
 /// Specific types for Day 17
@@ -100,6 +101,11 @@ pub mod flow {
         /// the jet pattern
         jet_pattern: D,
         shapes: S,
+
+        /// Number of jets consumed so far
+        jets_used: usize,
+        /// Number of rocks placed so far
+        rocks_placed: usize,
     }
 
     impl<D: DirectionIter, S: ShapeIter> std::fmt::Display for Chamber<D, S> {
@@ -127,6 +133,8 @@ pub mod flow {
                 occupied: HashSet::new(),
                 jet_pattern,
                 shapes,
+                jets_used: 0,
+                rocks_placed: 0,
             }
         }
 
@@ -156,6 +164,7 @@ pub mod flow {
 
             loop {
                 // move left or right
+                self.jets_used += 1;
                 let new_pos = match self.jet_pattern.next().unwrap() {
                     Direction::Left => pos - MOVE_X,
                     Direction::Right => pos + MOVE_X,
@@ -175,12 +184,39 @@ pub mod flow {
             // add SHAPE to OCCUPIED
             self.add(pos, shape);
             // update cavern height
-            self.highest_rock = self.highest_rock.max(pos.y + shape.height() - 1)
+            self.highest_rock = self.highest_rock.max(pos.y + shape.height() - 1);
+            self.rocks_placed += 1;
         }
 
         pub fn rock_tower_height(&self) -> usize {
             self.highest_rock
         }
+
+        pub fn jets_used(&self) -> usize {
+            self.jets_used
+        }
+
+        pub fn rocks_placed(&self) -> usize {
+            self.rocks_placed
+        }
+
+        /// Depth of the topmost rock in each column, relative to the highest rock
+        ///
+        /// Columns without rocks within `max_depth` report `max_depth`.
+        pub fn surface_profile(&self, max_depth: N) -> Vec<N> {
+            (1..=self.width)
+                .map(|x| {
+                    (0..max_depth)
+                        .find(|&d| {
+                            d < self.highest_rock
+                                && self
+                                    .occupied
+                                    .contains(&GridCoord::new(x, self.highest_rock - d))
+                        })
+                        .unwrap_or(max_depth)
+                })
+                .collect()
+        }
     }
 }
 
@@ -200,8 +236,38 @@ pub fn day17p1(input: &'static str) -> usize {
     chamber.rock_tower_height()
 }
 
-pub fn day17p2(_input: &str) -> usize {
-    0
+pub fn day17p2(input: &'static str) -> usize {
+    const ROCKS: usize = 1_000_000_000_000;
+
+    let jets = input.trim().len();
+    let jet_pattern = input.trim().chars().map(Direction::from).cycle();
+    let shapes = SHAPES.into_iter().cycle();
+    let mut chamber = Chamber::new(7, jet_pattern, shapes);
+
+    // The tower repeats once the same rock falls at the same point in the jet pattern onto
+    // the same surface.
+    let mut detector = CycleDetector::new();
+    let mut heights = vec![0];
+    let cycle = loop {
+        let key = (
+            chamber.jets_used() % jets,
+            chamber.rocks_placed() % SHAPES.len(),
+            chamber.surface_profile(32),
+        );
+        if let Some(cycle) = detector.observe(key) {
+            break cycle;
+        }
+        chamber.place_rock();
+        heights.push(chamber.rock_tower_height());
+    };
+
+    // make sure the history covers a full cycle
+    while heights.len() <= cycle.start + cycle.period {
+        chamber.place_rock();
+        heights.push(chamber.rock_tower_height());
+    }
+
+    cycle.extrapolate(&heights, ROCKS)
 }
 
 #[cfg(test)]
@@ -215,7 +281,7 @@ mod tests {
 
     #[test]
     fn part2_examples() {
-        assert_eq!(0, day17p2(INPUT));
+        assert_eq!(1514285714288, day17p2(INPUT));
     }
 
     const INPUT: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
@@ -1,9 +1,35 @@
-pub fn day20p1(_input: &str) -> usize {
-    0
+use common::ring::Ring;
+
+const DECRYPTION_KEY: i64 = 811589153;
+
+pub fn day20p1(input: &str) -> i64 {
+    decrypt(input, 1, 1)
+}
+
+pub fn day20p2(input: &str) -> i64 {
+    decrypt(input, DECRYPTION_KEY, 10)
 }
 
-pub fn day20p2(_input: &str) -> usize {
-    0
+fn decrypt(input: &str, key: i64, rounds: usize) -> i64 {
+    let mut file: Ring<i64> = input
+        .lines()
+        .map(|l| l.parse::<i64>().expect("number") * key)
+        .collect();
+
+    // handles are assigned in input order, which is the order numbers are moved in
+    for _ in 0..rounds {
+        for id in 0..file.len() {
+            let value = *file.get(id).unwrap();
+            let steps = value.rem_euclid(file.len() as i64 - 1);
+            file.move_by(id, steps as isize);
+        }
+    }
+
+    let zero = file.find(|&v| v == 0).expect("a zero");
+    [1000, 2000, 3000]
+        .into_iter()
+        .map(|k| file.get(file.offset(zero, k)).unwrap())
+        .sum()
 }
 
 #[cfg(test)]
@@ -12,13 +38,20 @@ mod tests {
 
     #[test]
     fn part1_examples() {
-        assert_eq!(0, day20p1(INPUT));
+        assert_eq!(3, day20p1(INPUT));
     }
 
     #[test]
     fn part2_examples() {
-        assert_eq!(0, day20p2(INPUT));
+        assert_eq!(1623178306, day20p2(INPUT));
     }
 
-    const INPUT: &str = "";
+    const INPUT: &str = "1
+2
+-3
+3
+-2
+0
+4
+";
 }
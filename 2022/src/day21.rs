@@ -1,9 +1,27 @@
-pub fn day21p1(_input: &str) -> usize {
-    0
+use common::expr::{Definitions, Expr, Grammar};
+
+pub fn day21p1(input: &str) -> i64 {
+    parse(input).eval("root").unwrap()
+}
+
+pub fn day21p2(input: &str) -> i64 {
+    let monkeys = parse(input);
+    let Some(Expr::Binary { lhs, rhs, .. }) = monkeys.get("root") else {
+        panic!("root must combine two monkeys");
+    };
+    let (Expr::Var(lhs), Expr::Var(rhs)) = (lhs.as_ref(), rhs.as_ref()) else {
+        panic!("root must combine two monkeys");
+    };
+
+    let humn = monkeys.solve_linear("humn", lhs, rhs).unwrap();
+    humn.to_integer().unwrap().try_into().unwrap()
 }
 
-pub fn day21p2(_input: &str) -> usize {
-    0
+fn parse(input: &str) -> Definitions<i64> {
+    Definitions::parse(input, &Grammar::arithmetic(), |line| {
+        line.split_once(": ").unwrap()
+    })
+    .unwrap()
 }
 
 #[cfg(test)]
@@ -12,13 +30,27 @@ mod tests {
 
     #[test]
     fn part1_examples() {
-        assert_eq!(0, day21p1(INPUT));
+        assert_eq!(152, day21p1(INPUT));
     }
 
     #[test]
     fn part2_examples() {
-        assert_eq!(0, day21p2(INPUT));
+        assert_eq!(301, day21p2(INPUT));
     }
 
-    const INPUT: &str = "";
+    const INPUT: &str = "root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32";
 }
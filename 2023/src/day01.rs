@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use common::text::{AhoCorasick, Match};
 
 pub fn day01p1(input: &str) -> u32 {
     input
@@ -16,33 +16,24 @@ pub fn day01p1(input: &str) -> u32 {
 }
 
 pub fn day01p2(input: &str) -> u32 {
-    const WORDS: [&str; 9] = [
-        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    const WORDS: [&str; 18] = [
+        "1", "2", "3", "4", "5", "6", "7", "8", "9", "one", "two", "three", "four", "five", "six",
+        "seven", "eight", "nine",
     ];
+    let digits = AhoCorasick::new(WORDS);
+    let value = |m: Match| (m.pattern % 9 + 1) as u32;
 
-    // build lookup table
-    let words = WORDS
-        .iter()
-        .enumerate()
-        .map(|(i, &w)| (w, (i + 1).to_string()))
-        .collect::<HashMap<_, _>>();
-
-    // convienience
-    #[inline]
-    fn starts_with_word(i: &str) -> Option<&'static str> {
-        WORDS.iter().find(|&w| i.starts_with(w)).copied()
-    }
-
-    // replace all words by digits
-    let mut input = String::from(input);
-    // longest word: 5 letters
-    for i in 0..(input.len() - 5) {
-        if let Some(word) = starts_with_word(&input[i..]) {
-            input.replace_range(i..(i + 1), words.get(word).unwrap());
-        }
-    }
-
-    day01p1(&input)
+    input
+        .lines()
+        .map(|line| {
+            // words overlap ("eightwo"), but none contains another, so ordering by end position
+            // is the same as ordering by start position
+            let mut matches = digits.find_overlapping(line.as_bytes());
+            let first = matches.next().unwrap();
+            let last = matches.last().unwrap_or(first);
+            value(first) * 10 + value(last)
+        })
+        .sum()
 }
 
 #[cfg(test)]
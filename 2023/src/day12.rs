@@ -1,9 +1,54 @@
-pub fn day12p1(_input: &str) -> u32 {
-    0
+use common::memo::Memo;
+
+pub fn day12p1(input: &str) -> u64 {
+    input.lines().map(|line| arrangements(line, 1)).sum()
+}
+
+pub fn day12p2(input: &str) -> u64 {
+    input.lines().map(|line| arrangements(line, 5)).sum()
+}
+
+/// Number of ways the unknown springs of a record can be filled in, after unfolding it `copies`
+/// times.
+fn arrangements(line: &str, copies: usize) -> u64 {
+    let (springs, groups) = line.split_once(' ').expect("springs and groups");
+    let springs = vec![springs; copies].join("?");
+    let groups: Vec<usize> = groups
+        .split(',')
+        .map(|g| g.parse().expect("group size"))
+        .collect::<Vec<_>>()
+        .repeat(copies);
+
+    count(&mut Memo::new(), springs.as_bytes(), &groups)
 }
 
-pub fn day12p2(_input: &str) -> u32 {
-    0
+/// The subproblems are suffixes of both lists, so their lengths identify them.
+fn count(memo: &mut Memo<(usize, usize), u64>, springs: &[u8], groups: &[usize]) -> u64 {
+    let Some((&group, rest)) = groups.split_first() else {
+        return (!springs.contains(&b'#')).into();
+    };
+
+    memo.get_or_compute((springs.len(), groups.len()), |memo| {
+        let mut total = 0;
+
+        // treat the first spring as operational
+        if let Some((&first, tail)) = springs.split_first() {
+            if first != b'#' {
+                total += count(memo, tail, groups);
+            }
+        }
+
+        // or start the group of damaged springs here
+        let fits = springs.len() >= group
+            && !springs[..group].contains(&b'.')
+            && springs.get(group) != Some(&b'#');
+        if fits {
+            let tail = springs.get(group + 1..).unwrap_or_default();
+            total += count(memo, tail, rest);
+        }
+
+        total
+    })
 }
 
 #[cfg(test)]
@@ -12,13 +57,22 @@ mod tests {
 
     #[test]
     fn part1_examples() {
-        assert_eq!(0, day12p1(INPUT));
+        assert_eq!(1, day12p1("???.### 1,1,3"));
+        assert_eq!(10, day12p1("?###???????? 3,2,1"));
+        assert_eq!(21, day12p1(INPUT));
     }
 
     #[test]
     fn part2_examples() {
-        assert_eq!(0, day12p2(INPUT));
+        assert_eq!(16384, day12p2(".??..??...?##. 1,1,3"));
+        assert_eq!(525152, day12p2(INPUT));
     }
 
-    const INPUT: &str = "";
+    const INPUT: &str = "???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1
+";
 }
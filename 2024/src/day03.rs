@@ -1,56 +1,40 @@
+use common::text::AhoCorasick;
+
 type I<'i> = &'i [u8];
 type N = u32;
 
-const PREFIX: I = b"mul(";
-
 const FN_MUL: I = b"mul(";
 const FN_DO: I = b"do()";
 const FN_DONT: I = b"don't()";
 
 // SAFETY: It is assumed that the input consists of valid ASCII characters only.
 
-pub fn day03p1(mut input: I) -> N {
-    let mut sum = 0;
+pub fn day03p1(input: I) -> N {
+    scan(input, false)
+}
 
-    while !input.is_empty() {
-        // Find the next occurence of `PREFIX`.
-        let Some(i) = input.windows(PREFIX.len()).position(|w| w == PREFIX) else {
-            // `PREFIX` was not found, we're probably done.
-            break;
-        };
-
-        // Skip over `PREFIX`.
-        input = &input[(i + PREFIX.len())..];
-
-        let (rest, left) = take_number(input);
-        input = rest;
-        let Some(left) = left else {
-            // No number was foud, move on.
-            continue;
-        };
-
-        // Expect a comma here.
-        if input[0] == b',' {
-            input = &input[1..];
-        } else {
-            continue;
-        }
+pub fn day03p2(input: I) -> N {
+    scan(input, true)
+}
 
-        let (rest, right) = take_number(input);
-        input = rest;
-        let Some(right) = right else {
-            // No number was foud, move on.
-            continue;
-        };
-
-        // Expect a closing bracket here.
-        if input[0] == b')' {
-            input = &input[1..];
-        } else {
-            continue;
-        }
+/// Sum up all valid `mul` instructions, optionally honouring `do()` and `don't()`.
+fn scan(input: I, conditionals: bool) -> N {
+    let tokens = AhoCorasick::new([FN_MUL, FN_DO, FN_DONT]);
+    let mut sum = 0;
+    let mut enabled = true;
 
-        sum += left * right;
+    // None of the tokens can occur inside a valid `mul(a,b)`, so every match is a candidate.
+    for m in tokens.find_overlapping(input) {
+        match m.pattern {
+            0 if enabled => {
+                if let (_, Some(product)) = mul(&input[m.start..]) {
+                    sum += product;
+                }
+            }
+            1 => enabled = true,
+            2 => enabled = !conditionals,
+            _ => {}
+        }
     }
 
     sum
@@ -80,37 +64,6 @@ fn take_number(mut input: I) -> (I, Option<N>) {
     (input, Some(num))
 }
 
-pub fn day03p2(mut input: I) -> N {
-    let mut sum = 0;
-    let mut enabled = true;
-
-    while !input.is_empty() {
-        match input {
-            i if i.starts_with(FN_MUL) => {
-                let (rest, product) = mul(input);
-                input = rest;
-                match product {
-                    Some(p) if enabled => sum += p,
-                    _ => {}
-                }
-            }
-            i if i.starts_with(FN_DONT) => {
-                enabled = false;
-                input = &input[FN_DONT.len()..];
-            }
-            i if i.starts_with(FN_DO) => {
-                enabled = true;
-                input = &input[FN_DO.len()..];
-            }
-            _ => {
-                input = &input[1..];
-            }
-        }
-    }
-
-    sum
-}
-
 fn mul(mut input: I) -> (I, Option<N>) {
     // Skip over `FN_MUL`
     input = &input[FN_MUL.len()..];
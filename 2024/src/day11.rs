@@ -1,9 +1,39 @@
-pub fn day11p1(_input: &str) -> u32 {
-    0
+use common::memo::Memo;
+
+pub fn day11p1(input: &str) -> u64 {
+    blink(input, 25)
+}
+
+pub fn day11p2(input: &str) -> u64 {
+    blink(input, 75)
 }
 
-pub fn day11p2(_input: &str) -> u32 {
-    0
+fn blink(input: &str, blinks: u32) -> u64 {
+    let mut memo = Memo::new();
+    input
+        .split_whitespace()
+        .map(|s| s.parse().expect("stone number"))
+        .map(|stone| stones(&mut memo, stone, blinks))
+        .sum()
+}
+
+/// Number of stones a single stone turns into after blinking `blinks` times.
+fn stones(memo: &mut Memo<(u64, u32), u64>, stone: u64, blinks: u32) -> u64 {
+    if blinks == 0 {
+        return 1;
+    }
+
+    memo.get_or_compute((stone, blinks), |memo| {
+        let digits = stone.checked_ilog10().unwrap_or(0) + 1;
+        if stone == 0 {
+            stones(memo, 1, blinks - 1)
+        } else if digits.is_multiple_of(2) {
+            let half = 10u64.pow(digits / 2);
+            stones(memo, stone / half, blinks - 1) + stones(memo, stone % half, blinks - 1)
+        } else {
+            stones(memo, stone * 2024, blinks - 1)
+        }
+    })
 }
 
 #[cfg(test)]
@@ -12,13 +42,15 @@ mod tests {
 
     #[test]
     fn part1_examples() {
-        assert_eq!(0, day11p1(INPUT));
+        assert_eq!(7, blink("0 1 10 99 999", 1));
+        assert_eq!(22, blink(INPUT, 6));
+        assert_eq!(55312, day11p1(INPUT));
     }
 
     #[test]
     fn part2_examples() {
-        assert_eq!(0, day11p2(INPUT));
+        assert_eq!(65601038650482, day11p2(INPUT));
     }
 
-    const INPUT: &str = "";
+    const INPUT: &str = "125 17\n";
 }
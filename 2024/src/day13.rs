@@ -1,9 +1,57 @@
-pub fn day13p1(_input: &str) -> u32 {
-    0
+use common::{
+    linalg::{cramer2, Rational},
+    ExpectPrefixExt,
+};
+
+pub fn day13p1(input: &str) -> u64 {
+    parse(input)
+        .filter_map(|m| m.presses(0))
+        .filter(|&(a, b)| a <= 100 && b <= 100)
+        .map(|(a, b)| 3 * a + b)
+        .sum()
+}
+
+pub fn day13p2(input: &str) -> u64 {
+    parse(input)
+        .filter_map(|m| m.presses(10_000_000_000_000))
+        .map(|(a, b)| 3 * a + b)
+        .sum()
+}
+
+struct Machine {
+    a: (i128, i128),
+    b: (i128, i128),
+    prize: (i128, i128),
+}
+
+impl Machine {
+    /// Number of presses of A and B to reach the prize moved by `offset`, if possible
+    fn presses(&self, offset: i128) -> Option<(u64, u64)> {
+        let m = [[self.a.0, self.b.0], [self.a.1, self.b.1]].map(|r| r.map(Rational::from));
+        let p = [self.prize.0 + offset, self.prize.1 + offset].map(Rational::from);
+        let [a, b] = cramer2(m, p)?;
+
+        let a = u64::try_from(a.to_integer()?).ok()?;
+        let b = u64::try_from(b.to_integer()?).ok()?;
+        Some((a, b))
+    }
+}
+
+fn parse(input: &str) -> impl Iterator<Item = Machine> + '_ {
+    input.split("\n\n").map(|block| {
+        let mut lines = block.lines();
+        let a = parse_pair(lines.next().unwrap().expect_prefix("Button A: "), '+');
+        let b = parse_pair(lines.next().unwrap().expect_prefix("Button B: "), '+');
+        let prize = parse_pair(lines.next().unwrap().expect_prefix("Prize: "), '=');
+        Machine { a, b, prize }
+    })
 }
 
-pub fn day13p2(_input: &str) -> u32 {
-    0
+/// Parse `X<sep>1, Y<sep>2`
+fn parse_pair(s: &str, sep: char) -> (i128, i128) {
+    let (x, y) = s.split_once(", ").unwrap();
+    let value = |v: &str| v.split_once(sep).unwrap().1.parse().unwrap();
+    (value(x), value(y))
 }
 
 #[cfg(test)]
@@ -12,13 +60,27 @@ mod tests {
 
     #[test]
     fn part1_examples() {
-        assert_eq!(0, day13p1(INPUT));
+        assert_eq!(480, day13p1(INPUT));
     }
 
     #[test]
     fn part2_examples() {
-        assert_eq!(0, day13p2(INPUT));
+        assert_eq!(875318608908, day13p2(INPUT));
     }
 
-    const INPUT: &str = "";
+    const INPUT: &str = "Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+
+Button A: X+17, Y+86
+Button B: X+84, Y+37
+Prize: X=7870, Y=6450
+
+Button A: X+69, Y+23
+Button B: X+27, Y+71
+Prize: X=18641, Y=10279";
 }
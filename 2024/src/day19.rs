@@ -1,9 +1,20 @@
-pub fn day19p1(_input: &str) -> u32 {
-    0
+use common::text::Trie;
+
+pub fn day19p1(input: &str) -> usize {
+    let (towels, designs) = parse(input);
+    designs
+        .filter(|d| towels.segmentations(d.as_bytes()) > 0)
+        .count()
+}
+
+pub fn day19p2(input: &str) -> u64 {
+    let (towels, designs) = parse(input);
+    designs.map(|d| towels.segmentations(d.as_bytes())).sum()
 }
 
-pub fn day19p2(_input: &str) -> u32 {
-    0
+fn parse(input: &str) -> (Trie, impl Iterator<Item = &str>) {
+    let (towels, designs) = input.split_once("\n\n").expect("blank line");
+    (towels.trim().split(", ").collect(), designs.lines())
 }
 
 #[cfg(test)]
@@ -12,13 +23,23 @@ mod tests {
 
     #[test]
     fn part1_examples() {
-        assert_eq!(0, day19p1(INPUT));
+        assert_eq!(6, day19p1(INPUT));
     }
 
     #[test]
     fn part2_examples() {
-        assert_eq!(0, day19p2(INPUT));
+        assert_eq!(16, day19p2(INPUT));
     }
 
-    const INPUT: &str = "";
+    const INPUT: &str = "r, wr, b, g, bwu, rb, gb, br
+
+brwrr
+bggr
+gbbr
+rrbgbr
+ubwu
+bwurrg
+brgr
+bbrgwb
+";
 }
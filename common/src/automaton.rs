@@ -0,0 +1,305 @@
+//! Cellular automata on dense grids and sparse sets of live cells
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use crate::{Grid, GridCoord};
+
+/// Which cells count as neighbours
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The four orthogonally adjacent cells, in reading order: up, left, right, down
+    VonNeumann,
+    /// All eight surrounding cells, in reading order
+    Moore,
+}
+
+impl Neighborhood {
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Neighborhood::VonNeumann => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Neighborhood::Moore => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// What lies beyond the edges of the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edges<T> {
+    /// Nothing; cells at the edge simply have fewer neighbours
+    Clip,
+    /// An infinite plane of cells in this state, which evolves under the same rule as the grid
+    Background(T),
+    /// The grid wraps around like a torus
+    Wrap,
+}
+
+/// Steps a grid of cells, where the next state of each cell depends on its current state and the
+/// states of its neighbours
+///
+/// Two grids are kept and swapped after every generation, so stepping does not allocate new grids.
+///
+/// # Examples
+///
+/// ```
+/// use common::{automaton::Automaton, Grid};
+///
+/// // game of life, with a blinker in the middle
+/// let grid = Grid::parse(".....\n..#..\n..#..\n..#..\n.....\n", |c| c == '#');
+/// let mut life = Automaton::new(grid);
+///
+/// let rule = |&alive: &bool, n: &[bool]| {
+///     let count = n.iter().filter(|&&c| c).count();
+///     count == 3 || (alive && count == 2)
+/// };
+/// assert!(life.step(rule));
+/// let render = |grid: &Grid<bool>| grid.render(|&c| if c { '#' } else { '.' });
+/// assert_eq!(".....\n.....\n.###.\n.....\n.....\n", render(life.grid()));
+///
+/// life.run(3, rule);
+/// assert_eq!(4, life.generation());
+/// assert_eq!(3, life.count(&true));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Automaton<T> {
+    grid: Grid<T>,
+    next: Grid<T>,
+    edges: Edges<T>,
+    neighborhood: Neighborhood,
+    generation: usize,
+}
+
+impl<T: Copy + PartialEq> Automaton<T> {
+    /// An automaton with the Moore neighbourhood and clipped edges
+    pub fn new(grid: Grid<T>) -> Self {
+        Self {
+            next: grid.clone(),
+            grid,
+            edges: Edges::Clip,
+            neighborhood: Neighborhood::Moore,
+            generation: 0,
+        }
+    }
+
+    pub fn with_neighborhood(mut self, neighborhood: Neighborhood) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    /// Surround the grid with an infinite background in state `value`
+    pub fn with_background(mut self, value: T) -> Self {
+        self.edges = Edges::Background(value);
+        self
+    }
+
+    /// Wrap around at the edges
+    pub fn with_wrapping(mut self) -> Self {
+        self.edges = Edges::Wrap;
+        self
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    /// Mutable access to the cells, e.g. to force some of them into a state between generations
+    pub fn grid_mut(&mut self) -> &mut Grid<T> {
+        &mut self.grid
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+
+    /// The state of the infinite background, if there is one
+    pub fn background(&self) -> Option<T> {
+        match self.edges {
+            Edges::Background(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Number of generations stepped so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Number of cells in state `value`, not counting the background
+    pub fn count(&self, value: &T) -> usize {
+        self.grid.cells().iter().filter(|c| *c == value).count()
+    }
+
+    /// Grow the grid by `margin` cells of background on every side
+    ///
+    /// Useful when the pattern can spread into the background.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no background.
+    pub fn pad(&mut self, margin: usize) {
+        let Edges::Background(bg) = self.edges else {
+            panic!("padding needs a background");
+        };
+
+        let (w, h) = (
+            self.grid.width() + 2 * margin,
+            self.grid.height() + 2 * margin,
+        );
+        let mut grid = Grid::new(w, h, bg);
+        for (pos, &cell) in self.grid.iter() {
+            grid[GridCoord::new(pos.x + margin, pos.y + margin)] = cell;
+        }
+        self.next = grid.clone();
+        self.grid = grid;
+    }
+
+    /// Advance one generation; returns whether any cell (or the background) changed
+    ///
+    /// `rule` is called with the current state of a cell and the states of its neighbours, in
+    /// the order given by the [`Neighborhood`].
+    pub fn step<F>(&mut self, mut rule: F) -> bool
+    where
+        F: FnMut(&T, &[T]) -> T,
+    {
+        let (w, h) = (self.grid.width(), self.grid.height());
+        let offsets = self.neighborhood.offsets();
+        let mut neighbors = Vec::with_capacity(offsets.len());
+        let mut changed = false;
+
+        for y in 0..h {
+            for x in 0..w {
+                neighbors.clear();
+                neighbors.extend(
+                    offsets
+                        .iter()
+                        .filter_map(|&(dx, dy)| self.neighbor(x, y, dx, dy)),
+                );
+
+                let pos = GridCoord::new(x, y);
+                let cell = &self.grid[pos];
+                let new = rule(cell, &neighbors);
+                changed |= new != *cell;
+                self.next[pos] = new;
+            }
+        }
+
+        if let Edges::Background(bg) = self.edges {
+            let new = rule(&bg, &vec![bg; offsets.len()]);
+            changed |= new != bg;
+            self.edges = Edges::Background(new);
+        }
+
+        std::mem::swap(&mut self.grid, &mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    /// Advance `n` generations
+    pub fn run<F>(&mut self, n: usize, mut rule: F)
+    where
+        F: FnMut(&T, &[T]) -> T,
+    {
+        for _ in 0..n {
+            self.step(&mut rule);
+        }
+    }
+
+    /// Step until a generation changes nothing; returns the number of that generation
+    pub fn run_until_stable<F>(&mut self, mut rule: F) -> usize
+    where
+        F: FnMut(&T, &[T]) -> T,
+    {
+        while self.step(&mut rule) {}
+        self.generation
+    }
+
+    fn neighbor(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<T> {
+        let (w, h) = (self.grid.width(), self.grid.height());
+        let pos = match self.edges {
+            Edges::Wrap => Some(GridCoord::new(
+                (x + w).wrapping_add_signed(dx) % w,
+                (y + h).wrapping_add_signed(dy) % h,
+            )),
+            _ => x
+                .checked_add_signed(dx)
+                .zip(y.checked_add_signed(dy))
+                .map(|(x, y)| GridCoord::new(x, y))
+                .filter(|&p| self.grid.in_bounds(p)),
+        };
+
+        match (pos, self.edges) {
+            (Some(pos), _) => Some(self.grid[pos]),
+            (None, Edges::Background(bg)) => Some(bg),
+            (None, _) => None,
+        }
+    }
+}
+
+/// Advance a sparse automaton with two states by one generation
+///
+/// `alive` holds the live cells of an unbounded space, `neighbors` lists the neighbours of a
+/// position, and `rule` decides from a cell's current state and its number of live neighbours
+/// whether it is alive in the next generation. Dead cells without live neighbours stay dead.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use common::automaton::step_set;
+///
+/// // 2020 day 17 example
+/// let neighbors = |(x, y, z): (i32, i32, i32)| {
+///     (-1..=1)
+///         .flat_map(move |dx| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| (dx, dy, dz))))
+///         .filter(|&d| d != (0, 0, 0))
+///         .map(move |(dx, dy, dz)| (x + dx, y + dy, z + dz))
+/// };
+/// let rule = |alive: bool, n: usize| n == 3 || (alive && n == 2);
+///
+/// let mut cells: HashSet<_> = [(1, 0, 0), (2, 1, 0), (0, 2, 0), (1, 2, 0), (2, 2, 0)].into();
+/// for _ in 0..6 {
+///     cells = step_set(&cells, neighbors, rule);
+/// }
+/// assert_eq!(112, cells.len());
+/// ```
+pub fn step_set<P, N, I, F>(alive: &HashSet<P>, neighbors: N, mut rule: F) -> HashSet<P>
+where
+    P: Copy + Eq + Hash,
+    N: Fn(P) -> I,
+    I: IntoIterator<Item = P>,
+    F: FnMut(bool, usize) -> bool,
+{
+    let mut counts: HashMap<P, usize> = HashMap::with_capacity(alive.len() * 4);
+    for &p in alive {
+        for n in neighbors(p) {
+            *counts.entry(n).or_default() += 1;
+        }
+    }
+
+    let mut next: HashSet<P> = counts
+        .iter()
+        .filter(|&(p, &n)| rule(alive.contains(p), n))
+        .map(|(&p, _)| p)
+        .collect();
+
+    // live cells without any live neighbours never show up in `counts`
+    for &p in alive {
+        if !counts.contains_key(&p) && rule(true, 0) {
+            next.insert(p);
+        }
+    }
+
+    next
+}
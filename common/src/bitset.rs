@@ -0,0 +1,479 @@
+//! Compact sets of small non-negative integers
+//!
+//! All set types are `Copy` (except [`BitVec`]), hashable and ordered, so they work well as
+//! `HashMap` keys for memoization.
+
+use std::{
+    fmt::Debug,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign},
+};
+
+/// Priority of an ASCII letter: `a..=z` are `1..=26`, `A..=Z` are `27..=52`
+///
+/// # Examples
+///
+/// ```
+/// use common::bitset::letter_priority;
+///
+/// assert_eq!(Some(16), letter_priority(b'p'));
+/// assert_eq!(Some(38), letter_priority(b'L'));
+/// assert_eq!(None, letter_priority(b'1'));
+/// ```
+pub fn letter_priority(c: u8) -> Option<usize> {
+    match c {
+        b'a'..=b'z' => Some((c - b'a') as usize + 1),
+        b'A'..=b'Z' => Some((c - b'A') as usize + 27),
+        _ => None,
+    }
+}
+
+macro_rules! fixed_bitset {
+    ($(#[$meta:meta])* $name:ident, $iter:ident, $t:ty) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name($t);
+
+        impl $name {
+            /// Number of elements the set can hold; valid elements are `0..CAPACITY`
+            pub const CAPACITY: usize = <$t>::BITS as usize;
+
+            /// The empty set
+            pub const fn new() -> Self {
+                Self(0)
+            }
+
+            /// Create a set from its raw bits
+            pub const fn from_bits(bits: $t) -> Self {
+                Self(bits)
+            }
+
+            /// The raw bits of the set
+            pub const fn bits(self) -> $t {
+                self.0
+            }
+
+            /// The set of all elements `0..n`
+            pub fn full(n: usize) -> Self {
+                assert!(n <= Self::CAPACITY, "{n} exceeds capacity");
+                match n {
+                    0 => Self(0),
+                    _ => Self(<$t>::MAX >> (Self::CAPACITY - n)),
+                }
+            }
+
+            /// Build the set of [`letter_priority`]s of all ASCII letters in `s`
+            ///
+            /// # Panics
+            ///
+            /// Panics on any character that is not an ASCII letter.
+            pub fn from_ascii_letters(s: &str) -> Self {
+                s.bytes()
+                    .map(|c| {
+                        letter_priority(c)
+                            .unwrap_or_else(|| panic!("not an ASCII letter: {:?}", c as char))
+                    })
+                    .collect()
+            }
+
+            /// Add `x`; returns whether it was newly inserted
+            pub fn insert(&mut self, x: usize) -> bool {
+                assert!(x < Self::CAPACITY, "{x} exceeds capacity");
+                let had = self.contains(x);
+                self.0 |= 1 << x;
+                !had
+            }
+
+            /// Remove `x`; returns whether it was present
+            pub fn remove(&mut self, x: usize) -> bool {
+                let had = self.contains(x);
+                if had {
+                    self.0 &= !(1 << x);
+                }
+                had
+            }
+
+            /// Return a copy of the set with `x` added
+            #[must_use]
+            pub fn with(mut self, x: usize) -> Self {
+                self.insert(x);
+                self
+            }
+
+            /// Return a copy of the set with `x` removed
+            #[must_use]
+            pub fn without(mut self, x: usize) -> Self {
+                self.remove(x);
+                self
+            }
+
+            pub fn contains(self, x: usize) -> bool {
+                x < Self::CAPACITY && self.0 & (1 << x) != 0
+            }
+
+            /// Number of elements
+            pub fn len(self) -> usize {
+                self.0.count_ones() as usize
+            }
+
+            pub fn is_empty(self) -> bool {
+                self.0 == 0
+            }
+
+            /// The smallest element
+            pub fn first(self) -> Option<usize> {
+                (!self.is_empty()).then(|| self.0.trailing_zeros() as usize)
+            }
+
+            /// The largest element
+            pub fn last(self) -> Option<usize> {
+                (!self.is_empty()).then(|| Self::CAPACITY - 1 - self.0.leading_zeros() as usize)
+            }
+
+            pub fn union(self, other: Self) -> Self {
+                Self(self.0 | other.0)
+            }
+
+            pub fn intersection(self, other: Self) -> Self {
+                Self(self.0 & other.0)
+            }
+
+            pub fn difference(self, other: Self) -> Self {
+                Self(self.0 & !other.0)
+            }
+
+            pub fn symmetric_difference(self, other: Self) -> Self {
+                Self(self.0 ^ other.0)
+            }
+
+            pub fn is_subset(self, other: Self) -> bool {
+                self.0 & !other.0 == 0
+            }
+
+            pub fn is_superset(self, other: Self) -> bool {
+                other.is_subset(self)
+            }
+
+            pub fn is_disjoint(self, other: Self) -> bool {
+                self.0 & other.0 == 0
+            }
+
+            /// Iterate over the elements in ascending order
+            pub fn iter(self) -> $iter {
+                $iter(self.0)
+            }
+        }
+
+        impl Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_set().entries(self.iter()).finish()
+            }
+        }
+
+        impl FromIterator<usize> for $name {
+            fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+                let mut set = Self::new();
+                set.extend(iter);
+                set
+            }
+        }
+
+        impl Extend<usize> for $name {
+            fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+                for x in iter {
+                    self.insert(x);
+                }
+            }
+        }
+
+        impl IntoIterator for $name {
+            type Item = usize;
+            type IntoIter = $iter;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        impl BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, rhs: Self) -> Self {
+                self.union(rhs)
+            }
+        }
+
+        impl BitOrAssign for $name {
+            fn bitor_assign(&mut self, rhs: Self) {
+                *self = self.union(rhs);
+            }
+        }
+
+        impl BitAnd for $name {
+            type Output = Self;
+
+            fn bitand(self, rhs: Self) -> Self {
+                self.intersection(rhs)
+            }
+        }
+
+        impl BitAndAssign for $name {
+            fn bitand_assign(&mut self, rhs: Self) {
+                *self = self.intersection(rhs);
+            }
+        }
+
+        impl BitXor for $name {
+            type Output = Self;
+
+            fn bitxor(self, rhs: Self) -> Self {
+                self.symmetric_difference(rhs)
+            }
+        }
+
+        impl BitXorAssign for $name {
+            fn bitxor_assign(&mut self, rhs: Self) {
+                *self = self.symmetric_difference(rhs);
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                self.difference(rhs)
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = self.difference(rhs);
+            }
+        }
+
+        /// Iterator over the elements of a bitset, in ascending order
+        #[derive(Debug, Clone)]
+        pub struct $iter($t);
+
+        impl Iterator for $iter {
+            type Item = usize;
+
+            fn next(&mut self) -> Option<usize> {
+                if self.0 == 0 {
+                    return None;
+                }
+                let x = self.0.trailing_zeros() as usize;
+                // clear the lowest set bit
+                self.0 &= self.0 - 1;
+                Some(x)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let n = self.0.count_ones() as usize;
+                (n, Some(n))
+            }
+        }
+
+        impl ExactSizeIterator for $iter {}
+    };
+}
+
+fixed_bitset!(
+    /// A set of integers in `0..64`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::bitset::BitSet64;
+    ///
+    /// // 2022 day 3: the item type in both compartments of a rucksack
+    /// let (l, r) = "vJrwpWtwJgWrhcsFMMfFFhFp".split_at(12);
+    /// let common = BitSet64::from_ascii_letters(l) & BitSet64::from_ascii_letters(r);
+    /// assert_eq!(vec![16], common.iter().collect::<Vec<_>>());
+    ///
+    /// let mut visited = BitSet64::new();
+    /// assert!(visited.insert(3));
+    /// assert!(!visited.insert(3));
+    /// assert_eq!(BitSet64::from_iter([1, 3]), visited.with(1));
+    /// ```
+    BitSet64,
+    BitSet64Iter,
+    u64
+);
+
+fixed_bitset!(
+    /// A set of integers in `0..128`, e.g. ASCII characters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::bitset::BitSet128;
+    ///
+    /// let chars = "mjqj".bytes().map(usize::from).collect::<BitSet128>();
+    /// assert_eq!(3, chars.len());
+    /// assert!(chars.contains(b'q' as usize));
+    /// assert_eq!(Some(b'j' as usize), chars.first());
+    /// assert_eq!(Some(b'q' as usize), chars.last());
+    /// ```
+    BitSet128,
+    BitSet128Iter,
+    u128
+);
+
+/// A growable set of non-negative integers
+///
+/// # Examples
+///
+/// ```
+/// use common::bitset::BitVec;
+///
+/// let mut a = BitVec::new();
+/// a.insert(3);
+/// a.insert(200);
+/// let b = BitVec::from_iter([3, 4]);
+///
+/// assert_eq!(vec![3, 4, 200], a.union(&b).iter().collect::<Vec<_>>());
+/// assert_eq!(BitVec::from_iter([3]), a.intersection(&b));
+/// assert_eq!(BitVec::from_iter([200]), a.difference(&b));
+///
+/// // equality ignores capacity
+/// a.remove(200);
+/// assert_eq!(BitVec::from_iter([3]), a);
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BitVec {
+    /// Invariant: never ends in a zero word, so equal sets compare and hash equally
+    words: Vec<u64>,
+}
+
+impl BitVec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `x`; returns whether it was newly inserted
+    pub fn insert(&mut self, x: usize) -> bool {
+        let (w, b) = (x / 64, x % 64);
+        if w >= self.words.len() {
+            self.words.resize(w + 1, 0);
+        }
+        let had = self.words[w] & (1 << b) != 0;
+        self.words[w] |= 1 << b;
+        !had
+    }
+
+    /// Remove `x`; returns whether it was present
+    pub fn remove(&mut self, x: usize) -> bool {
+        if !self.contains(x) {
+            return false;
+        }
+        self.words[x / 64] &= !(1 << (x % 64));
+        self.trim();
+        true
+    }
+
+    pub fn contains(&self, x: usize) -> bool {
+        self.words
+            .get(x / 64)
+            .is_some_and(|w| w & (1 << (x % 64)) != 0)
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut set = self.clone();
+        set.union_with(other);
+        set
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut set = self.clone();
+        set.intersect_with(other);
+        set
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut set = self.clone();
+        set.difference_with(other);
+        set
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.words.truncate(other.words.len());
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= b;
+        }
+        self.trim();
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= !b;
+        }
+        self.trim();
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.words.len() <= other.words.len()
+            && self
+                .words
+                .iter()
+                .zip(&other.words)
+                .all(|(a, b)| a & !b == 0)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.words.iter().zip(&other.words).all(|(a, b)| a & b == 0)
+    }
+
+    /// Iterate over the elements in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &w)| BitSet64Iter(w).map(move |b| i * 64 + b))
+    }
+
+    fn trim(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+}
+
+impl Debug for BitVec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<usize> for BitVec {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<usize> for BitVec {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for x in iter {
+            self.insert(x);
+        }
+    }
+}
@@ -0,0 +1,594 @@
+//! Permutations, combinations, subsets and compositions
+//!
+//! Every iterator implements [`Iterator`] yielding owned `Vec`s for convenience, and also has a
+//! `next_slice` method that reuses an internal buffer and does not allocate. For branch and
+//! bound searches, the `*_pruned` functions walk the same sequences depth-first and skip every
+//! branch whose partial result is rejected by a `keep` callback.
+
+/// Rearrange `items` into the next lexicographically greater permutation
+///
+/// Returns `false` (and leaves `items` sorted ascending) if `items` was the last permutation.
+///
+/// # Examples
+///
+/// ```
+/// use common::combinatorics::next_permutation;
+///
+/// let mut v = [1, 2, 3];
+/// assert!(next_permutation(&mut v));
+/// assert_eq!([1, 3, 2], v);
+///
+/// let mut v = [3, 2, 1];
+/// assert!(!next_permutation(&mut v));
+/// assert_eq!([1, 2, 3], v);
+/// ```
+pub fn next_permutation<T: Ord>(items: &mut [T]) -> bool {
+    let Some(i) = items.windows(2).rposition(|w| w[0] < w[1]) else {
+        items.reverse();
+        return false;
+    };
+
+    let j = items.iter().rposition(|x| *x > items[i]).unwrap();
+    items.swap(i, j);
+    items[i + 1..].reverse();
+    true
+}
+
+/// All permutations of `items`, in lexicographic order of their positions
+///
+/// # Examples
+///
+/// ```
+/// use common::combinatorics::permutations;
+///
+/// let p = permutations(&['a', 'b', 'c']).collect::<Vec<_>>();
+/// assert_eq!(6, p.len());
+/// assert_eq!(vec!['a', 'b', 'c'], p[0]);
+/// assert_eq!(vec!['a', 'c', 'b'], p[1]);
+/// assert_eq!(vec!['c', 'b', 'a'], p[5]);
+/// ```
+pub fn permutations<T: Clone>(items: &[T]) -> Permutations<T> {
+    Permutations {
+        items: items.to_vec(),
+        indices: (0..items.len()).collect(),
+        buf: Vec::with_capacity(items.len()),
+        started: false,
+        done: false,
+    }
+}
+
+/// Iterator returned by [`permutations`]
+#[derive(Debug, Clone)]
+pub struct Permutations<T> {
+    items: Vec<T>,
+    indices: Vec<usize>,
+    buf: Vec<T>,
+    started: bool,
+    done: bool,
+}
+
+impl<T: Clone> Permutations<T> {
+    /// Advance to the next permutation without allocating
+    pub fn next_slice(&mut self) -> Option<&[T]> {
+        if self.done {
+            return None;
+        }
+        if self.started && !next_permutation(&mut self.indices) {
+            self.done = true;
+            return None;
+        }
+        self.started = true;
+
+        self.buf.clear();
+        self.buf
+            .extend(self.indices.iter().map(|&i| self.items[i].clone()));
+        Some(&self.buf)
+    }
+}
+
+impl<T: Clone> Iterator for Permutations<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_slice().map(<[T]>::to_vec)
+    }
+}
+
+/// All permutations of `items` using Heap's algorithm
+///
+/// Each permutation differs from the previous one by a single swap, which makes this the
+/// cheapest way to visit every ordering when the order does not matter.
+///
+/// # Examples
+///
+/// ```
+/// use common::combinatorics::heap_permutations;
+///
+/// let mut p = heap_permutations(&[1, 2, 3]);
+/// assert_eq!(Some(&[1, 2, 3][..]), p.next_slice());
+/// assert_eq!(Some(&[2, 1, 3][..]), p.next_slice());
+/// assert_eq!(4, p.count());
+/// ```
+pub fn heap_permutations<T: Clone>(items: &[T]) -> HeapPermutations<T> {
+    HeapPermutations {
+        items: items.to_vec(),
+        counters: vec![0; items.len()],
+        i: 0,
+        started: false,
+    }
+}
+
+/// Iterator returned by [`heap_permutations`]
+#[derive(Debug, Clone)]
+pub struct HeapPermutations<T> {
+    items: Vec<T>,
+    counters: Vec<usize>,
+    i: usize,
+    started: bool,
+}
+
+impl<T> HeapPermutations<T> {
+    /// Advance to the next permutation without allocating
+    pub fn next_slice(&mut self) -> Option<&[T]> {
+        if !self.started {
+            self.started = true;
+            return Some(&self.items);
+        }
+
+        // iterative Heap's algorithm, resumed where the last call left off
+        while self.i < self.items.len() {
+            if self.counters[self.i] < self.i {
+                if self.i.is_multiple_of(2) {
+                    self.items.swap(0, self.i);
+                } else {
+                    self.items.swap(self.counters[self.i], self.i);
+                }
+                self.counters[self.i] += 1;
+                self.i = 0;
+                return Some(&self.items);
+            }
+            self.counters[self.i] = 0;
+            self.i += 1;
+        }
+        None
+    }
+}
+
+impl<T: Clone> Iterator for HeapPermutations<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_slice().map(<[T]>::to_vec)
+    }
+}
+
+/// All ways to pick `k` of `items`, keeping their relative order
+///
+/// # Examples
+///
+/// ```
+/// use common::combinatorics::combinations;
+///
+/// let c = combinations(&[1, 2, 3, 4], 2).collect::<Vec<_>>();
+/// assert_eq!(
+///     vec![vec![1, 2], vec![1, 3], vec![1, 4], vec![2, 3], vec![2, 4], vec![3, 4]],
+///     c,
+/// );
+/// assert_eq!(1, combinations(&[1, 2], 0).count());
+/// assert_eq!(0, combinations(&[1, 2], 3).count());
+/// ```
+pub fn combinations<T: Clone>(items: &[T], k: usize) -> Combinations<T> {
+    Combinations {
+        items: items.to_vec(),
+        indices: (0..k).collect(),
+        buf: Vec::with_capacity(k),
+        started: false,
+        done: k > items.len(),
+    }
+}
+
+/// Iterator returned by [`combinations`]
+#[derive(Debug, Clone)]
+pub struct Combinations<T> {
+    items: Vec<T>,
+    indices: Vec<usize>,
+    buf: Vec<T>,
+    started: bool,
+    done: bool,
+}
+
+impl<T: Clone> Combinations<T> {
+    /// Advance to the next combination without allocating
+    pub fn next_slice(&mut self) -> Option<&[T]> {
+        if self.done {
+            return None;
+        }
+
+        if self.started {
+            let (n, k) = (self.items.len(), self.indices.len());
+            // rightmost index that can still move right
+            let Some(i) = (0..k).rev().find(|&i| self.indices[i] < n - k + i) else {
+                self.done = true;
+                return None;
+            };
+            self.indices[i] += 1;
+            for j in i + 1..k {
+                self.indices[j] = self.indices[j - 1] + 1;
+            }
+        }
+        self.started = true;
+
+        self.buf.clear();
+        self.buf
+            .extend(self.indices.iter().map(|&i| self.items[i].clone()));
+        Some(&self.buf)
+    }
+}
+
+impl<T: Clone> Iterator for Combinations<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_slice().map(<[T]>::to_vec)
+    }
+}
+
+/// All subsets of `items`, starting with the empty set
+///
+/// Subsets are enumerated by counting a bitmask upwards, so at most 63 items are supported.
+///
+/// # Examples
+///
+/// ```
+/// use common::combinatorics::power_set;
+///
+/// let s = power_set(&['a', 'b']).collect::<Vec<_>>();
+/// assert_eq!(vec![vec![], vec!['a'], vec!['b'], vec!['a', 'b']], s);
+/// ```
+pub fn power_set<T: Clone>(items: &[T]) -> PowerSet<T> {
+    assert!(items.len() < 64, "power set of {} items", items.len());
+    PowerSet {
+        items: items.to_vec(),
+        buf: Vec::with_capacity(items.len()),
+        mask: 0,
+    }
+}
+
+/// Iterator returned by [`power_set`]
+#[derive(Debug, Clone)]
+pub struct PowerSet<T> {
+    items: Vec<T>,
+    buf: Vec<T>,
+    mask: u64,
+}
+
+impl<T: Clone> PowerSet<T> {
+    /// Advance to the next subset without allocating
+    pub fn next_slice(&mut self) -> Option<&[T]> {
+        if self.mask >> self.items.len() != 0 {
+            return None;
+        }
+
+        self.buf.clear();
+        self.buf.extend(
+            self.items
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| self.mask & (1 << i) != 0)
+                .map(|(_, x)| x.clone()),
+        );
+        self.mask += 1;
+        Some(&self.buf)
+    }
+}
+
+impl<T: Clone> Iterator for PowerSet<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_slice().map(<[T]>::to_vec)
+    }
+}
+
+/// All ways to write `n` as the sum of `parts` non-negative numbers, in lexicographic order
+///
+/// # Examples
+///
+/// ```
+/// use common::combinatorics::compositions;
+///
+/// let c = compositions(2, 2).collect::<Vec<_>>();
+/// assert_eq!(vec![vec![0, 2], vec![1, 1], vec![2, 0]], c);
+///
+/// // 2015 day 15: splitting 100 teaspoons between four ingredients
+/// assert_eq!(176_851, compositions(100, 4).count());
+/// ```
+pub fn compositions(n: usize, parts: usize) -> Compositions {
+    let mut buf = vec![0; parts];
+    if let Some(last) = buf.last_mut() {
+        *last = n;
+    }
+    Compositions {
+        buf,
+        started: false,
+        done: parts == 0 && n > 0,
+    }
+}
+
+/// Iterator returned by [`compositions`]
+#[derive(Debug, Clone)]
+pub struct Compositions {
+    buf: Vec<usize>,
+    started: bool,
+    done: bool,
+}
+
+impl Compositions {
+    /// Advance to the next composition without allocating
+    pub fn next_slice(&mut self) -> Option<&[usize]> {
+        if self.done {
+            return None;
+        }
+
+        if self.started {
+            // rightmost part that has something behind it to take from
+            let k = self.buf.len();
+            let mut rest = 0;
+            let mut found = None;
+            for i in (0..k.saturating_sub(1)).rev() {
+                rest += self.buf[i + 1];
+                if rest > 0 {
+                    found = Some(i);
+                    break;
+                }
+            }
+            let Some(i) = found else {
+                self.done = true;
+                return None;
+            };
+
+            self.buf[i] += 1;
+            self.buf[i + 1..].fill(0);
+            self.buf[k - 1] = rest - 1;
+        }
+        self.started = true;
+
+        Some(&self.buf)
+    }
+}
+
+impl Iterator for Compositions {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_slice().map(<[usize]>::to_vec)
+    }
+}
+
+/// Visit all permutations of `items`, skipping every branch whose prefix is rejected by `keep`
+///
+/// `keep` is called with every non-empty prefix; returning `false` prunes all permutations
+/// starting with it. `visit` is called with every complete permutation.
+///
+/// # Examples
+///
+/// ```
+/// use common::combinatorics::permutations_pruned;
+///
+/// // routes starting with 'a' only
+/// let mut routes = vec![];
+/// permutations_pruned(&['a', 'b', 'c'], |p| p[0] == 'a', |p| routes.push(p.to_vec()));
+/// assert_eq!(vec![vec!['a', 'b', 'c'], vec!['a', 'c', 'b']], routes);
+/// ```
+pub fn permutations_pruned<T, K, V>(items: &[T], mut keep: K, mut visit: V)
+where
+    T: Clone,
+    K: FnMut(&[T]) -> bool,
+    V: FnMut(&[T]),
+{
+    fn go<T: Clone>(
+        items: &mut [T],
+        depth: usize,
+        keep: &mut impl FnMut(&[T]) -> bool,
+        visit: &mut impl FnMut(&[T]),
+    ) {
+        if depth == items.len() {
+            visit(items);
+            return;
+        }
+        for i in depth..items.len() {
+            // rotate instead of swapping so the remaining items keep their order
+            items[depth..=i].rotate_right(1);
+            if keep(&items[..=depth]) {
+                go(items, depth + 1, keep, visit);
+            }
+            items[depth..=i].rotate_left(1);
+        }
+    }
+
+    go(&mut items.to_vec(), 0, &mut keep, &mut visit);
+}
+
+/// Visit all `k`-combinations of `items`, skipping every branch rejected by `keep`
+///
+/// `keep` is called with every non-empty partial combination; `visit` with every complete one.
+///
+/// # Examples
+///
+/// ```
+/// use common::combinatorics::combinations_pruned;
+///
+/// // pairs with a sum of at most 5
+/// let mut n = 0;
+/// combinations_pruned(&[1, 2, 3, 4], 2, |c| c.iter().sum::<i32>() <= 5, |_| n += 1);
+/// assert_eq!(4, n);
+/// ```
+pub fn combinations_pruned<T, K, V>(items: &[T], k: usize, mut keep: K, mut visit: V)
+where
+    T: Clone,
+    K: FnMut(&[T]) -> bool,
+    V: FnMut(&[T]),
+{
+    fn go<T: Clone>(
+        items: &[T],
+        k: usize,
+        start: usize,
+        chosen: &mut Vec<T>,
+        keep: &mut impl FnMut(&[T]) -> bool,
+        visit: &mut impl FnMut(&[T]),
+    ) {
+        if chosen.len() == k {
+            visit(chosen);
+            return;
+        }
+        let needed = k - chosen.len();
+        for i in start..=(items.len() - needed) {
+            chosen.push(items[i].clone());
+            if keep(chosen) {
+                go(items, k, i + 1, chosen, keep, visit);
+            }
+            chosen.pop();
+        }
+    }
+
+    if k <= items.len() {
+        go(
+            items,
+            k,
+            0,
+            &mut Vec::with_capacity(k),
+            &mut keep,
+            &mut visit,
+        );
+    }
+}
+
+/// Visit all subsets of `items`, skipping every branch rejected by `keep`
+///
+/// `keep` is called every time an item is added to the subset, with the subset so far; if it
+/// returns `false`, no superset containing that selection is visited. `visit` is called with
+/// every subset (including the empty one), together with the mask of selected indices.
+///
+/// # Examples
+///
+/// ```
+/// use common::combinatorics::subsets_pruned;
+///
+/// // 2015 day 17: container combinations holding exactly 25 litres
+/// let containers = [20, 15, 10, 5, 5];
+/// let mut n = 0;
+/// subsets_pruned(
+///     &containers,
+///     |s| s.iter().sum::<u32>() <= 25,
+///     |s, _| n += (s.iter().sum::<u32>() == 25) as usize,
+/// );
+/// assert_eq!(4, n);
+/// ```
+pub fn subsets_pruned<T, K, V>(items: &[T], mut keep: K, mut visit: V)
+where
+    T: Clone,
+    K: FnMut(&[T]) -> bool,
+    V: FnMut(&[T], &[bool]),
+{
+    fn go<T: Clone>(
+        items: &[T],
+        i: usize,
+        chosen: &mut Vec<T>,
+        mask: &mut Vec<bool>,
+        keep: &mut impl FnMut(&[T]) -> bool,
+        visit: &mut impl FnMut(&[T], &[bool]),
+    ) {
+        if i == items.len() {
+            visit(chosen, mask);
+            return;
+        }
+
+        mask.push(false);
+        go(items, i + 1, chosen, mask, keep, visit);
+        mask.pop();
+
+        chosen.push(items[i].clone());
+        if keep(chosen) {
+            mask.push(true);
+            go(items, i + 1, chosen, mask, keep, visit);
+            mask.pop();
+        }
+        chosen.pop();
+    }
+
+    go(
+        items,
+        0,
+        &mut Vec::with_capacity(items.len()),
+        &mut Vec::with_capacity(items.len()),
+        &mut keep,
+        &mut visit,
+    );
+}
+
+/// Visit all compositions of `n` into `parts`, skipping every branch rejected by `keep`
+///
+/// `keep` is called with every non-empty prefix of assigned parts; `visit` with every complete
+/// composition.
+///
+/// # Examples
+///
+/// ```
+/// use common::combinatorics::compositions_pruned;
+///
+/// // no part may be larger than 2
+/// let mut all = vec![];
+/// compositions_pruned(4, 3, |p| *p.last().unwrap() <= 2, |c| all.push(c.to_vec()));
+/// assert_eq!(
+///     vec![
+///         vec![0, 2, 2],
+///         vec![1, 1, 2],
+///         vec![1, 2, 1],
+///         vec![2, 0, 2],
+///         vec![2, 1, 1],
+///         vec![2, 2, 0],
+///     ],
+///     all,
+/// );
+/// ```
+pub fn compositions_pruned<K, V>(n: usize, parts: usize, mut keep: K, mut visit: V)
+where
+    K: FnMut(&[usize]) -> bool,
+    V: FnMut(&[usize]),
+{
+    fn go(
+        remaining: usize,
+        parts: usize,
+        prefix: &mut Vec<usize>,
+        keep: &mut impl FnMut(&[usize]) -> bool,
+        visit: &mut impl FnMut(&[usize]),
+    ) {
+        if prefix.len() + 1 == parts {
+            prefix.push(remaining);
+            if keep(prefix) {
+                visit(prefix);
+            }
+            prefix.pop();
+            return;
+        }
+        for x in 0..=remaining {
+            prefix.push(x);
+            if keep(prefix) {
+                go(remaining - x, parts, prefix, keep, visit);
+            }
+            prefix.pop();
+        }
+    }
+
+    match parts {
+        0 if n == 0 => visit(&[]),
+        0 => {}
+        _ => go(
+            n,
+            parts,
+            &mut Vec::with_capacity(parts),
+            &mut keep,
+            &mut visit,
+        ),
+    }
+}
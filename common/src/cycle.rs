@@ -0,0 +1,227 @@
+//! Cycle detection for simulations that eventually repeat
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt::Debug,
+    hash::Hash,
+    ops::{Add, Mul, Sub},
+};
+
+/// A detected cycle: after `start` steps, the sequence repeats every `period` steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    /// Length of the prefix before the cycle begins
+    pub start: usize,
+    /// Length of the cycle
+    pub period: usize,
+}
+
+impl Cycle {
+    pub const fn new(start: usize, period: usize) -> Self {
+        Self { start, period }
+    }
+
+    /// The earliest step that is in the same state as step `n`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::cycle::Cycle;
+    ///
+    /// let c = Cycle::new(3, 4);
+    /// assert_eq!(2, c.equivalent_step(2));
+    /// assert_eq!(5, c.equivalent_step(9));
+    /// assert_eq!(6, c.equivalent_step(1_000_000_002));
+    /// ```
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+
+    /// Extrapolate a metric to step `n`
+    ///
+    /// `history[i]` is the value of the metric after step `i`. It must cover at least one full
+    /// cycle, i.e. `history.len() > start + period`. The metric is assumed to grow by the same
+    /// amount every period, like the height of a tower that is being stacked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::cycle::Cycle;
+    ///
+    /// // grows 1, 2, 3 (prefix), then +2, +5 repeating
+    /// let history = [0u64, 1, 3, 6, 8, 13, 15];
+    /// let c = Cycle::new(3, 2);
+    ///
+    /// assert_eq!(3, c.extrapolate(&history, 2));
+    /// assert_eq!(20, c.extrapolate(&history, 7));
+    /// assert_eq!(6 + 7 * 1_000_000, c.extrapolate(&history, 3 + 2 * 1_000_000));
+    /// ```
+    pub fn extrapolate<T>(&self, history: &[T], n: usize) -> T
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + TryFrom<usize>,
+        <T as TryFrom<usize>>::Error: Debug,
+    {
+        assert!(
+            history.len() > self.start + self.period,
+            "history must cover a full cycle"
+        );
+
+        if n < history.len() {
+            return history[n];
+        }
+
+        let cycles = T::try_from((n - self.start) / self.period).unwrap();
+        let per_cycle = history[self.start + self.period] - history[self.start];
+        history[self.equivalent_step(n)] + cycles * per_cycle
+    }
+}
+
+/// Find the cycle in the sequence `x0, f(x0), f(f(x0)), ...` using Brent's algorithm
+///
+/// Only needs to keep two states in memory, but calls `f` roughly three times per step.
+///
+/// # Examples
+///
+/// ```
+/// use common::cycle::{brent, Cycle};
+///
+/// // 2, 4, 16, 256 % 100 = 56, 36, 96, 16, ...
+/// assert_eq!(Cycle::new(2, 4), brent(2u32, |&x| x * x % 100));
+/// ```
+pub fn brent<S, F>(x0: S, mut f: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> S,
+{
+    // find the period: search successive powers of two
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = x0.clone();
+    let mut hare = f(&x0);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = f(&hare);
+        period += 1;
+    }
+
+    // find the start: move a second pointer `period` steps ahead, then advance both
+    let mut tortoise = x0.clone();
+    let mut hare = x0;
+    for _ in 0..period {
+        hare = f(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        start += 1;
+    }
+
+    Cycle { start, period }
+}
+
+/// Find the cycle in `x0, f(x0), ...` by remembering a key for every state seen
+///
+/// Use this when the full state is expensive to compare, but a smaller key identifies it, or
+/// when `f` is too expensive to call repeatedly.
+///
+/// # Examples
+///
+/// ```
+/// use common::cycle::{find_cycle, Cycle};
+///
+/// // state is (step, value), but only the value matters for repetition
+/// let c = find_cycle((0, 2u32), |&(i, x)| (i + 1, x * x % 100), |&(_, x)| x);
+/// assert_eq!(Cycle::new(2, 4), c);
+/// ```
+pub fn find_cycle<S, K, F, G>(x0: S, mut f: F, mut key: G) -> Cycle
+where
+    K: Hash + Eq,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+{
+    let mut detector = CycleDetector::new();
+    let mut state = x0;
+    loop {
+        if let Some(cycle) = detector.observe(key(&state)) {
+            return cycle;
+        }
+        state = f(&state);
+    }
+}
+
+/// Step-by-step cycle detection for simulations that are driven externally
+///
+/// Call [`Self::observe`] with a key describing the state after every step, starting with the
+/// initial state as step 0.
+///
+/// # Examples
+///
+/// ```
+/// use common::cycle::{Cycle, CycleDetector};
+///
+/// let mut detector = CycleDetector::new();
+/// let mut heights = vec![];
+/// let mut cycle = None;
+/// for (step, jet) in "abcbcbc".chars().enumerate() {
+///     heights.push(step * 10);
+///     cycle = detector.observe(jet);
+///     if cycle.is_some() {
+///         break;
+///     }
+/// }
+///
+/// let cycle = cycle.unwrap();
+/// assert_eq!(Cycle::new(1, 2), cycle);
+/// assert_eq!(1_000_000_000 * 10, cycle.extrapolate(&heights, 1_000_000_000));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CycleDetector<K> {
+    seen: HashMap<K, usize>,
+    step: usize,
+}
+
+impl<K> Default for CycleDetector<K> {
+    fn default() -> Self {
+        Self {
+            seen: HashMap::new(),
+            step: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq> CycleDetector<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the key for the next step, returning the cycle if the key was seen before
+    pub fn observe(&mut self, key: K) -> Option<Cycle> {
+        let step = self.step;
+        self.step += 1;
+
+        match self.seen.entry(key) {
+            Entry::Occupied(e) => Some(Cycle {
+                start: *e.get(),
+                period: step - e.get(),
+            }),
+            Entry::Vacant(e) => {
+                e.insert(step);
+                None
+            }
+        }
+    }
+
+    /// Number of steps observed so far
+    pub fn steps(&self) -> usize {
+        self.step
+    }
+}
@@ -0,0 +1,198 @@
+use crate::Grid;
+
+/// Union-find over the elements `0..n`, with path compression and union by rank
+///
+/// # Examples
+///
+/// ```
+/// use common::DisjointSet;
+///
+/// let mut ds = DisjointSet::new(6);
+/// ds.union(0, 1);
+/// ds.union(1, 2);
+/// ds.union(4, 5);
+///
+/// assert!(ds.same(0, 2));
+/// assert!(!ds.same(2, 3));
+/// assert_eq!(3, ds.count());
+/// assert_eq!(3, ds.size_of(1));
+/// assert_eq!(vec![vec![0, 1, 2], vec![3], vec![4, 5]], ds.components());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl DisjointSet {
+    /// Create `n` singleton sets
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+            size: vec![1; n],
+            count: n,
+        }
+    }
+
+    /// Build the regions of a grid, where orthogonally adjacent cells `a` and `b` belong to the
+    /// same region if `connected(a, b)` holds
+    ///
+    /// Elements are the cell indices, see [`Grid::index_of`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::{DisjointSet, Grid, GridCoord};
+    ///
+    /// let garden = Grid::parse("AAAA\nBBCD\nBBCC\nEEEC\n", |c| c);
+    /// let mut regions = DisjointSet::from_grid(&garden, |a, b| a == b);
+    ///
+    /// assert_eq!(5, regions.count());
+    /// let c = garden.index_of(GridCoord::new(2, 1));
+    /// assert_eq!(4, regions.size_of(c));
+    /// ```
+    pub fn from_grid<T, F>(grid: &Grid<T>, mut connected: F) -> Self
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut ds = Self::new(grid.cells().len());
+
+        // only look right and down, the other directions are covered by the neighbours
+        for (pos, cell) in grid.iter() {
+            let idx = grid.index_of(pos);
+            let right = (pos.x + 1 < grid.width()).then(|| idx + 1);
+            let down = (pos.y + 1 < grid.height()).then(|| idx + grid.width());
+            for other in [right, down].into_iter().flatten() {
+                if connected(cell, &grid.cells()[other]) {
+                    ds.union(idx, other);
+                }
+            }
+        }
+
+        ds
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Whether there are no elements
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Number of disjoint sets
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The representative of the set containing `x`
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        // path compression: point everything on the way directly to the root
+        let mut x = x;
+        while self.parent[x] != root {
+            let next = self.parent[x];
+            self.parent[x] = root;
+            x = next;
+        }
+
+        root
+    }
+
+    /// Merge the sets containing `a` and `b`. Returns `false` if they were already merged.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        if self.rank[a] < self.rank[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        if self.rank[a] == self.rank[b] {
+            self.rank[a] += 1;
+        }
+        self.count -= 1;
+
+        true
+    }
+
+    /// Whether `a` and `b` are in the same set
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the set containing `x`
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Sizes of all sets, ordered by their smallest element
+    pub fn sizes(&mut self) -> Vec<usize> {
+        self.components().iter().map(Vec::len).collect()
+    }
+
+    /// A dense label `0..count` for every element; labels are ordered by the smallest element of
+    /// each set
+    pub fn labels(&mut self) -> Vec<usize> {
+        let mut label_of_root = vec![usize::MAX; self.len()];
+        let mut next = 0;
+
+        (0..self.len())
+            .map(|x| {
+                let root = self.find(x);
+                if label_of_root[root] == usize::MAX {
+                    label_of_root[root] = next;
+                    next += 1;
+                }
+                label_of_root[root]
+            })
+            .collect()
+    }
+
+    /// All sets, ordered by their smallest element, with elements in ascending order
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut components = vec![Vec::new(); self.count];
+        for (x, label) in self.labels().into_iter().enumerate() {
+            components[label].push(x);
+        }
+        components
+    }
+}
+
+/// Label the regions of a grid; see [`DisjointSet::from_grid`]
+///
+/// Returns a grid of labels `0..n`, and the number of regions `n`.
+///
+/// # Examples
+///
+/// ```
+/// use common::{label_regions, Grid};
+///
+/// let map = Grid::parse("2199\n3989\n9856\n", |c| c.to_digit(10).unwrap());
+/// let (labels, n) = label_regions(&map, |&a, &b| a != 9 && b != 9);
+///
+/// // every 9 is a region of its own
+/// assert_eq!(7, n);
+/// assert_eq!("0012\n0345\n6444\n", labels.render(|&l| char::from(b'0' + l as u8)));
+/// ```
+pub fn label_regions<T, F>(grid: &Grid<T>, connected: F) -> (Grid<usize>, usize)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let mut ds = DisjointSet::from_grid(grid, connected);
+    let labels = ds.labels();
+    (Grid::from_vec(grid.width(), labels), ds.count())
+}
@@ -0,0 +1,668 @@
+//! Parsing and evaluating expressions, and graphs of named expressions
+//!
+//! The operators are not fixed: a [`Grammar`] lists them together with their precedence and
+//! what they compute, so the same parser handles `old * 19` as well as `x LSHIFT 2`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+    ops::{Add, Div, Mul, Sub},
+    str::FromStr,
+};
+
+use crate::linalg::Rational;
+
+/// A parsed expression
+///
+/// Operator nodes carry the function that computes them, so an expression can be evaluated
+/// without the grammar it was parsed with.
+#[derive(Debug, Clone)]
+pub enum Expr<V> {
+    Num(V),
+    Var(String),
+    Unary {
+        op: &'static str,
+        apply: fn(V) -> V,
+        arg: Box<Expr<V>>,
+    },
+    Binary {
+        op: &'static str,
+        apply: fn(V, V) -> V,
+        lhs: Box<Expr<V>>,
+        rhs: Box<Expr<V>>,
+    },
+}
+
+impl<V: Copy> Expr<V> {
+    /// Evaluate the expression, looking up variables with `env`
+    ///
+    /// Returns `None` if `env` does not know one of the variables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::expr::Grammar;
+    ///
+    /// let expr = Grammar::<u64>::arithmetic().parse("old * (old + 3)").unwrap();
+    /// assert_eq!(Some(40), expr.eval(&mut |v| (v == "old").then_some(5)));
+    /// assert_eq!(None, expr.eval(&mut |_| None));
+    /// ```
+    pub fn eval<F>(&self, env: &mut F) -> Option<V>
+    where
+        F: FnMut(&str) -> Option<V>,
+    {
+        Some(match self {
+            Expr::Num(n) => *n,
+            Expr::Var(name) => env(name)?,
+            Expr::Unary { apply, arg, .. } => apply(arg.eval(env)?),
+            Expr::Binary {
+                apply, lhs, rhs, ..
+            } => apply(lhs.eval(env)?, rhs.eval(env)?),
+        })
+    }
+}
+
+impl<V> Expr<V> {
+    /// All variables referenced by the expression, in order of appearance (with repetitions)
+    pub fn variables(&self) -> Vec<&str> {
+        let mut vars = Vec::new();
+        self.collect_variables(&mut vars);
+        vars
+    }
+
+    fn collect_variables<'a>(&'a self, vars: &mut Vec<&'a str>) {
+        match self {
+            Expr::Num(_) => {}
+            Expr::Var(name) => vars.push(name),
+            Expr::Unary { arg, .. } => arg.collect_variables(vars),
+            Expr::Binary { lhs, rhs, .. } => {
+                lhs.collect_variables(vars);
+                rhs.collect_variables(vars);
+            }
+        }
+    }
+}
+
+impl<V: fmt::Display> fmt::Display for Expr<V> {
+    /// Infix notation, with every operator application in parentheses
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Num(n) => write!(f, "{n}"),
+            Expr::Var(name) => write!(f, "{name}"),
+            Expr::Unary { op, arg, .. } => write!(f, "({op} {arg})"),
+            Expr::Binary { op, lhs, rhs, .. } => write!(f, "({lhs} {op} {rhs})"),
+        }
+    }
+}
+
+/// A parse error, with the byte offset where it occurred
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, Clone)]
+struct BinaryOp<V> {
+    symbol: &'static str,
+    precedence: u8,
+    right_assoc: bool,
+    apply: fn(V, V) -> V,
+}
+
+#[derive(Debug, Clone)]
+struct PrefixOp<V> {
+    symbol: &'static str,
+    precedence: u8,
+    apply: fn(V) -> V,
+}
+
+/// The operators an expression may use
+///
+/// Operators are either made of symbols like `+` or `<<`, or words like `AND`. Higher precedence
+/// binds tighter. Operands are numbers (parsed with [`FromStr`]), identifiers and parenthesized
+/// sub-expressions.
+///
+/// # Examples
+///
+/// ```
+/// use common::expr::Grammar;
+///
+/// // 2015 day 7
+/// let circuit = Grammar::<u16>::new()
+///     .binary("AND", 1, |a, b| a & b)
+///     .binary("OR", 1, |a, b| a | b)
+///     .binary("LSHIFT", 1, |a, b| a << b)
+///     .binary("RSHIFT", 1, |a, b| a >> b)
+///     .prefix("NOT", 2, |a| !a);
+///
+/// let expr = circuit.parse("NOT x OR y LSHIFT 2").unwrap();
+/// assert_eq!("(((NOT x) OR y) LSHIFT 2)", expr.to_string());
+///
+/// let pow = Grammar::<u32>::arithmetic().right_binary("^", 3, u32::pow);
+/// assert_eq!("(2 * (3 ^ (2 ^ 2)))", pow.parse("2 * 3 ^ 2 ^ 2").unwrap().to_string());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Grammar<V> {
+    binary: Vec<BinaryOp<V>>,
+    prefix: Vec<PrefixOp<V>>,
+}
+
+impl<V> Default for Grammar<V> {
+    fn default() -> Self {
+        Self {
+            binary: Vec::new(),
+            prefix: Vec::new(),
+        }
+    }
+}
+
+impl<V> Grammar<V>
+where
+    V: Copy + Add<Output = V> + Sub<Output = V> + Mul<Output = V> + Div<Output = V>,
+{
+    /// `+`, `-`, `*` and `/` with the usual precedence, all left associative
+    pub fn arithmetic() -> Self {
+        Self::new()
+            .binary("+", 1, |a, b| a + b)
+            .binary("-", 1, |a, b| a - b)
+            .binary("*", 2, |a, b| a * b)
+            .binary("/", 2, |a, b| a / b)
+    }
+}
+
+impl<V> Grammar<V> {
+    /// A grammar without any operators
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a left associative binary operator
+    pub fn binary(mut self, symbol: &'static str, precedence: u8, apply: fn(V, V) -> V) -> Self {
+        self.binary.push(BinaryOp {
+            symbol,
+            precedence,
+            right_assoc: false,
+            apply,
+        });
+        self
+    }
+
+    /// Add a right associative binary operator
+    pub fn right_binary(
+        mut self,
+        symbol: &'static str,
+        precedence: u8,
+        apply: fn(V, V) -> V,
+    ) -> Self {
+        self.binary.push(BinaryOp {
+            symbol,
+            precedence,
+            right_assoc: true,
+            apply,
+        });
+        self
+    }
+
+    /// Add a prefix operator
+    pub fn prefix(mut self, symbol: &'static str, precedence: u8, apply: fn(V) -> V) -> Self {
+        self.prefix.push(PrefixOp {
+            symbol,
+            precedence,
+            apply,
+        });
+        self
+    }
+
+    fn is_operator(&self, s: &str) -> bool {
+        self.binary.iter().any(|op| op.symbol == s) || self.prefix.iter().any(|op| op.symbol == s)
+    }
+}
+
+impl<V: Copy + FromStr> Grammar<V> {
+    /// Parse an expression
+    pub fn parse(&self, input: &str) -> Result<Expr<V>, ParseError> {
+        let mut parser = Parser {
+            grammar: self,
+            tokens: self.tokenize(input)?,
+            pos: 0,
+            end: input.len(),
+        };
+        let expr = parser.expr(0)?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(expr),
+            Some(&(offset, _)) => Err(ParseError {
+                offset,
+                message: "expected end of expression".to_string(),
+            }),
+        }
+    }
+
+    fn tokenize<'a>(&self, input: &'a str) -> Result<Vec<(usize, Token<'a>)>, ParseError> {
+        let mut tokens = Vec::new();
+        let mut pos = 0;
+
+        while pos < input.len() {
+            let rest = &input[pos..];
+            let c = rest.chars().next().unwrap();
+            let word_len = |pred: fn(char) -> bool| rest.find(|c| !pred(c)).unwrap_or(rest.len());
+
+            let (len, token) = if c.is_whitespace() {
+                (c.len_utf8(), None)
+            } else if c.is_ascii_digit() {
+                let len = word_len(|c| c.is_ascii_alphanumeric() || c == '.');
+                (len, Some(Token::Num(&rest[..len])))
+            } else if c.is_alphabetic() || c == '_' {
+                let len = word_len(|c| c.is_alphanumeric() || c == '_');
+                let word = &rest[..len];
+                match self.is_operator(word) {
+                    true => (len, Some(Token::Op(word))),
+                    false => (len, Some(Token::Ident(word))),
+                }
+            } else if c == '(' || c == ')' {
+                (1, Some(Token::Paren(c)))
+            } else {
+                // the longest symbolic operator that matches
+                let symbol = self
+                    .binary
+                    .iter()
+                    .map(|op| op.symbol)
+                    .chain(self.prefix.iter().map(|op| op.symbol))
+                    .filter(|s| rest.starts_with(s))
+                    .max_by_key(|s| s.len())
+                    .ok_or_else(|| ParseError {
+                        offset: pos,
+                        message: format!("unexpected character {c:?}"),
+                    })?;
+                (symbol.len(), Some(Token::Op(&rest[..symbol.len()])))
+            };
+
+            if let Some(token) = token {
+                tokens.push((pos, token));
+            }
+            pos += len;
+        }
+
+        Ok(tokens)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Num(&'a str),
+    Ident(&'a str),
+    Op(&'a str),
+    Paren(char),
+}
+
+struct Parser<'g, 'a, V> {
+    grammar: &'g Grammar<V>,
+    tokens: Vec<(usize, Token<'a>)>,
+    pos: usize,
+    end: usize,
+}
+
+impl<'a, V: Copy + FromStr> Parser<'_, 'a, V> {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            offset: self.tokens.get(self.pos).map_or(self.end, |&(o, _)| o),
+            message: message.to_string(),
+        }
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.tokens.get(self.pos).map(|&(_, t)| t);
+        self.pos += 1;
+        token
+    }
+
+    /// Parse an expression whose operators all bind at least as tight as `min_precedence`
+    fn expr(&mut self, min_precedence: u8) -> Result<Expr<V>, ParseError> {
+        let mut lhs = self.operand()?;
+
+        while let Some(&(_, Token::Op(symbol))) = self.tokens.get(self.pos) {
+            let Some(op) = self.grammar.binary.iter().find(|op| op.symbol == symbol) else {
+                return Err(self.error("expected a binary operator"));
+            };
+            if op.precedence < min_precedence {
+                break;
+            }
+            self.pos += 1;
+
+            let next = if op.right_assoc {
+                op.precedence
+            } else {
+                op.precedence + 1
+            };
+            lhs = Expr::Binary {
+                op: op.symbol,
+                apply: op.apply,
+                lhs: Box::new(lhs),
+                rhs: Box::new(self.expr(next)?),
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    fn operand(&mut self) -> Result<Expr<V>, ParseError> {
+        let start = self.pos;
+        match self.next() {
+            Some(Token::Num(n)) => n.parse().map(Expr::Num).map_err(|_| {
+                self.pos = start;
+                self.error("invalid number")
+            }),
+            Some(Token::Ident(name)) => Ok(Expr::Var(name.to_string())),
+            Some(Token::Paren('(')) => {
+                let expr = self.expr(0)?;
+                match self.next() {
+                    Some(Token::Paren(')')) => Ok(expr),
+                    _ => {
+                        self.pos -= 1;
+                        Err(self.error("expected `)`"))
+                    }
+                }
+            }
+            Some(Token::Op(symbol)) => {
+                let grammar = self.grammar;
+                let Some(op) = grammar.prefix.iter().find(|op| op.symbol == symbol) else {
+                    self.pos = start;
+                    return Err(self.error("expected an operand"));
+                };
+                Ok(Expr::Unary {
+                    op: op.symbol,
+                    apply: op.apply,
+                    arg: Box::new(self.expr(op.precedence)?),
+                })
+            }
+            _ => {
+                self.pos = start;
+                Err(self.error("expected an operand"))
+            }
+        }
+    }
+}
+
+/// Named expressions that may refer to each other by name
+///
+/// Names without a definition are inputs; they have to be provided when evaluating.
+///
+/// # Examples
+///
+/// ```
+/// use common::expr::{Definitions, Grammar};
+///
+/// // 2022 day 21, shortened
+/// let input = "root: pppw + sjmn\npppw: cczh / lfqf\ncczh: 6 + humn\nlfqf: 4\nsjmn: 2 * 6\nhumn: 5\n";
+/// let monkeys = Definitions::parse(input, &Grammar::<i64>::arithmetic(), |l| {
+///     l.split_once(": ").unwrap()
+/// })
+/// .unwrap();
+///
+/// assert_eq!(Some(14), monkeys.eval("root"));
+/// assert_eq!(6, monkeys.eval_all().unwrap().len());
+///
+/// // which value of `humn` makes both sides of `root` equal?
+/// let humn = monkeys.solve_linear("humn", "pppw", "sjmn").unwrap();
+/// assert_eq!(Some(42), humn.to_integer());
+///
+/// // cyclic definitions have no value
+/// let cyclic = Definitions::parse("a: b + x\nb: 2 * a\n", &Grammar::<i64>::arithmetic(), |l| {
+///     l.split_once(": ").unwrap()
+/// })
+/// .unwrap();
+/// assert_eq!(None, cyclic.eval("a"));
+/// assert_eq!(None, cyclic.solve_linear("x", "a", "b"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Definitions<V> {
+    exprs: HashMap<String, Expr<V>>,
+}
+
+impl<V> Default for Definitions<V> {
+    fn default() -> Self {
+        Self {
+            exprs: HashMap::new(),
+        }
+    }
+}
+
+impl<V> Definitions<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define `name`, replacing any previous definition
+    pub fn insert(&mut self, name: &str, expr: Expr<V>) {
+        self.exprs.insert(name.to_string(), expr);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Expr<V>> {
+        self.exprs.get(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.exprs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exprs.is_empty()
+    }
+
+    /// All defined names in an order where every name comes after the names it depends on
+    ///
+    /// Returns `None` if the definitions are cyclic.
+    pub fn topological_order(&self) -> Option<Vec<&str>> {
+        // Kahn's algorithm, only counting dependencies on other definitions
+        let mut pending: HashMap<&str, usize> = HashMap::new();
+        let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (name, expr) in &self.exprs {
+            let deps: HashSet<_> = expr
+                .variables()
+                .into_iter()
+                .filter(|v| self.exprs.contains_key(*v))
+                .collect();
+            pending.insert(name, deps.len());
+            for dep in deps {
+                dependents.entry(dep).or_default().push(name);
+            }
+        }
+
+        let mut order: Vec<&str> = pending
+            .iter()
+            .filter(|&(_, &n)| n == 0)
+            .map(|(&name, _)| name)
+            .collect();
+        let mut i = 0;
+        while let Some(&name) = order.get(i) {
+            for &d in dependents.get(name).into_iter().flatten() {
+                let n = pending.get_mut(d).unwrap();
+                *n -= 1;
+                if *n == 0 {
+                    order.push(d);
+                }
+            }
+            i += 1;
+        }
+
+        (order.len() == self.exprs.len()).then_some(order)
+    }
+}
+
+impl<V: Copy + FromStr> Definitions<V> {
+    /// Parse one definition per line; `split` separates a line into name and expression
+    pub fn parse<F>(input: &str, grammar: &Grammar<V>, mut split: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(&str) -> (&str, &str),
+    {
+        let mut defs = Self::new();
+        for line in input.lines().filter(|l| !l.trim().is_empty()) {
+            let (name, expr) = split(line);
+            defs.insert(name.trim(), grammar.parse(expr)?);
+        }
+        Ok(defs)
+    }
+}
+
+impl<V: Copy> Definitions<V> {
+    /// The value of `name`, evaluating each definition it depends on once
+    ///
+    /// Returns `None` if `name` depends on an undefined name or on itself.
+    pub fn eval(&self, name: &str) -> Option<V> {
+        let mut memo = HashMap::new();
+        self.eval_memo(name, &mut memo, &mut HashSet::new())
+    }
+
+    /// The values of all definitions, evaluated in topological order
+    ///
+    /// Returns `None` if a definition depends on an undefined name, or the definitions are
+    /// cyclic.
+    pub fn eval_all(&self) -> Option<HashMap<String, V>> {
+        let mut values: HashMap<String, V> = HashMap::with_capacity(self.exprs.len());
+        for name in self.topological_order()? {
+            let value = self.exprs[name].eval(&mut |v| values.get(v).copied())?;
+            values.insert(name.to_string(), value);
+        }
+        Some(values)
+    }
+
+    fn eval_memo<'a>(
+        &'a self,
+        name: &'a str,
+        memo: &mut HashMap<&'a str, V>,
+        visiting: &mut HashSet<&'a str>,
+    ) -> Option<V> {
+        if let Some(&v) = memo.get(name) {
+            return Some(v);
+        }
+        let (name, expr) = self.exprs.get_key_value(name)?;
+        if !visiting.insert(name) {
+            return None;
+        }
+
+        for dep in expr.variables() {
+            self.eval_memo(dep, memo, visiting)?;
+        }
+        let value = expr.eval(&mut |v| memo.get(v).copied())?;
+
+        visiting.remove(name.as_str());
+        memo.insert(name, value);
+        Some(value)
+    }
+}
+
+/// `a * x + b` for the unknown `x`
+#[derive(Debug, Clone, Copy)]
+struct Linear {
+    a: Rational,
+    b: Rational,
+}
+
+impl Linear {
+    fn constant(b: Rational) -> Self {
+        Self {
+            a: Rational::zero(),
+            b,
+        }
+    }
+}
+
+impl<V: Copy + Into<i128>> Definitions<V> {
+    /// The value of `unknown` that makes `lhs` and `rhs` evaluate to the same value
+    ///
+    /// The definition of `unknown` itself is ignored. Only `+`, `-`, `*`, `/` and unary `-` are
+    /// understood, and the unknown may not be multiplied by itself or appear in a divisor.
+    /// Calculations are exact, so integer division in the definitions is treated as exact
+    /// division. Returns `None` if there is no single solution, the expressions are not linear,
+    /// or they depend on a cyclic definition.
+    pub fn solve_linear(&self, unknown: &str, lhs: &str, rhs: &str) -> Option<Rational> {
+        let (mut memo, mut visiting) = (HashMap::new(), HashSet::new());
+        let l = self.linear(lhs, unknown, &mut memo, &mut visiting)?;
+        let r = self.linear(rhs, unknown, &mut memo, &mut visiting)?;
+
+        // a1 x + b1 = a2 x + b2  =>  x = (b2 - b1) / (a1 - a2)
+        let a = l.a - r.a;
+        (!a.is_zero()).then(|| (r.b - l.b) / a)
+    }
+
+    fn linear<'a>(
+        &'a self,
+        name: &'a str,
+        unknown: &str,
+        memo: &mut HashMap<&'a str, Linear>,
+        visiting: &mut HashSet<&'a str>,
+    ) -> Option<Linear> {
+        if name == unknown {
+            return Some(Linear {
+                a: Rational::one(),
+                b: Rational::zero(),
+            });
+        }
+        if let Some(&l) = memo.get(name) {
+            return Some(l);
+        }
+
+        let (name, expr) = self.exprs.get_key_value(name)?;
+        if !visiting.insert(name) {
+            return None;
+        }
+        let l = self.linear_expr(expr, unknown, memo, visiting)?;
+        visiting.remove(name.as_str());
+        memo.insert(name, l);
+        Some(l)
+    }
+
+    fn linear_expr<'a>(
+        &'a self,
+        expr: &'a Expr<V>,
+        unknown: &str,
+        memo: &mut HashMap<&'a str, Linear>,
+        visiting: &mut HashSet<&'a str>,
+    ) -> Option<Linear> {
+        Some(match expr {
+            Expr::Num(n) => Linear::constant(Rational::from((*n).into())),
+            Expr::Var(name) => self.linear(name, unknown, memo, visiting)?,
+            Expr::Unary { op: "-", arg, .. } => {
+                let l = self.linear_expr(arg, unknown, memo, visiting)?;
+                Linear { a: -l.a, b: -l.b }
+            }
+            Expr::Binary { op, lhs, rhs, .. } => {
+                let l = self.linear_expr(lhs, unknown, memo, visiting)?;
+                let r = self.linear_expr(rhs, unknown, memo, visiting)?;
+                match *op {
+                    "+" => Linear {
+                        a: l.a + r.a,
+                        b: l.b + r.b,
+                    },
+                    "-" => Linear {
+                        a: l.a - r.a,
+                        b: l.b - r.b,
+                    },
+                    "*" if l.a.is_zero() => Linear {
+                        a: r.a * l.b,
+                        b: r.b * l.b,
+                    },
+                    "*" if r.a.is_zero() => Linear {
+                        a: l.a * r.b,
+                        b: l.b * r.b,
+                    },
+                    "/" if r.a.is_zero() && !r.b.is_zero() => Linear {
+                        a: l.a / r.b,
+                        b: l.b / r.b,
+                    },
+                    _ => return None,
+                }
+            }
+            Expr::Unary { .. } => return None,
+        })
+    }
+}
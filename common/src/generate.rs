@@ -6,7 +6,7 @@ use std::{fmt::Debug, str::FromStr};
 ///
 /// ```
 /// use common::generate::ints;
-
+///
 /// assert_eq!(vec![0,2,3,-4], ints("0\n2\n+3\n-4\n"));
 /// ```
 pub fn ints<T>(input: &str) -> Vec<T>
@@ -23,7 +23,7 @@ where
 ///
 /// ```
 /// use common::generate::int_list;
-
+///
 /// assert_eq!(vec![3,4,3,1,2], int_list("3,4,3,1,2\n"));
 /// ```
 pub fn int_list<T>(input: &str) -> Vec<T>
@@ -0,0 +1,211 @@
+//! Area and containment for simple polygons on the integer lattice
+//!
+//! Polygons are given as their list of vertices in order (either direction); the last vertex is
+//! implicitly connected back to the first.
+
+use crate::{math::gcd, GridCoord};
+
+/// Coordinate type used by the polygon helpers; large enough for part 2 sized inputs
+pub type Point = GridCoord<i64>;
+
+/// Where a point lies relative to a polygon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Containment {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// Walk a path of `(direction, distance)` steps from `start`, returning the visited corners
+///
+/// The starting point is included; the final point is not if the path returns to `start`.
+///
+/// # Examples
+///
+/// ```
+/// use common::geometry::{trace, Point};
+///
+/// let (r, d, l, u) = (Point::new(1, 0), Point::new(0, 1), Point::new(-1, 0), Point::new(0, -1));
+/// let corners = trace(Point::new(0, 0), [(r, 2), (d, 2), (l, 2), (u, 2)]);
+///
+/// assert_eq!(
+///     vec![Point::new(0, 0), Point::new(2, 0), Point::new(2, 2), Point::new(0, 2)],
+///     corners,
+/// );
+/// ```
+pub fn trace(start: Point, steps: impl IntoIterator<Item = (Point, i64)>) -> Vec<Point> {
+    let mut vertices = vec![start];
+    let mut pos = start;
+    for (dir, n) in steps {
+        pos = Point::new(pos.x + dir.x * n, pos.y + dir.y * n);
+        vertices.push(pos);
+    }
+    if vertices.len() > 1 && vertices.last() == Some(&start) {
+        vertices.pop();
+    }
+    vertices
+}
+
+/// Twice the area of the polygon, using the shoelace formula
+///
+/// Doubling keeps the result exact, since lattice polygons can have half-integer areas.
+///
+/// # Examples
+///
+/// ```
+/// use common::geometry::{double_area, Point};
+///
+/// let triangle = [Point::new(0, 0), Point::new(3, 0), Point::new(0, 3)];
+/// assert_eq!(9, double_area(&triangle));
+/// ```
+pub fn double_area(vertices: &[Point]) -> i64 {
+    edges(vertices)
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum::<i64>()
+        .abs()
+}
+
+/// Number of lattice points on the polygon's boundary
+///
+/// # Examples
+///
+/// ```
+/// use common::geometry::{boundary_points, Point};
+///
+/// let triangle = [Point::new(0, 0), Point::new(3, 0), Point::new(0, 3)];
+/// assert_eq!(9, boundary_points(&triangle));
+/// ```
+pub fn boundary_points(vertices: &[Point]) -> i64 {
+    edges(vertices)
+        .map(|(a, b)| gcd(b.x - a.x, b.y - a.y))
+        .sum()
+}
+
+/// Number of lattice points strictly inside the polygon, using Pick's theorem
+///
+/// Polygons without area, like fewer than three vertices or vertices on a single line, enclose
+/// nothing and give 0.
+///
+/// # Examples
+///
+/// ```
+/// use common::geometry::{interior_points, Point};
+///
+/// let triangle = [Point::new(0, 0), Point::new(3, 0), Point::new(0, 3)];
+/// assert_eq!(1, interior_points(&triangle));
+/// assert_eq!(0, interior_points(&triangle[..2]));
+/// assert_eq!(0, interior_points(&[]));
+///
+/// let line = [Point::new(0, 0), Point::new(3, 0), Point::new(6, 0)];
+/// assert_eq!(0, interior_points(&line));
+/// ```
+pub fn interior_points(vertices: &[Point]) -> i64 {
+    let area = double_area(vertices);
+    if area == 0 {
+        return 0;
+    }
+    // A = I + B/2 - 1  =>  2I = 2A - B + 2
+    (area - boundary_points(vertices) + 2) / 2
+}
+
+/// Number of lattice points inside or on the boundary of the polygon
+///
+/// When the polygon is the path of a one cell wide trench through the centres of grid cells,
+/// this is the total number of cells dug out.
+///
+/// A polygon without area is a path that is walked there and back again, so its boundary counts
+/// every point twice, except for the two ends.
+///
+/// # Examples
+///
+/// ```
+/// use common::geometry::{covered_points, trace, Point};
+///
+/// // 2023 day 18 example
+/// let plan = "R 6,D 5,L 2,D 2,R 2,D 2,L 5,U 2,L 1,U 2,R 2,U 3,L 2,U 2";
+/// let steps = plan.split(',').map(|s| {
+///     let (dir, n) = s.split_once(' ').unwrap();
+///     let dir = match dir {
+///         "R" => Point::new(1, 0),
+///         "D" => Point::new(0, 1),
+///         "L" => Point::new(-1, 0),
+///         _ => Point::new(0, -1),
+///     };
+///     (dir, n.parse().unwrap())
+/// });
+///
+/// assert_eq!(62, covered_points(&trace(Point::new(0, 0), steps)));
+///
+/// // degenerate polygons
+/// assert_eq!(0, covered_points(&[]));
+/// assert_eq!(1, covered_points(&[Point::new(2, 5)]));
+/// assert_eq!(4, covered_points(&[Point::new(0, 0), Point::new(3, 0)]));
+/// assert_eq!(7, covered_points(&[Point::new(0, 0), Point::new(3, 0), Point::new(6, 0)]));
+/// ```
+pub fn covered_points(vertices: &[Point]) -> i64 {
+    if vertices.is_empty() {
+        return 0;
+    }
+    let boundary = boundary_points(vertices);
+    if double_area(vertices) == 0 {
+        return boundary / 2 + 1;
+    }
+    interior_points(vertices) + boundary
+}
+
+/// Whether `p` lies inside, outside or on the boundary of the polygon, by ray casting
+///
+/// # Examples
+///
+/// ```
+/// use common::geometry::{contains, Containment, Point};
+///
+/// let square = [Point::new(0, 0), Point::new(4, 0), Point::new(4, 4), Point::new(0, 4)];
+///
+/// assert_eq!(Containment::Inside, contains(&square, Point::new(2, 2)));
+/// assert_eq!(Containment::Boundary, contains(&square, Point::new(4, 1)));
+/// assert_eq!(Containment::Outside, contains(&square, Point::new(5, 2)));
+/// ```
+pub fn contains(vertices: &[Point], p: Point) -> Containment {
+    let mut inside = false;
+
+    for (a, b) in edges(vertices) {
+        if on_segment(a, b, p) {
+            return Containment::Boundary;
+        }
+
+        // count crossings of a ray going from `p` towards +x; the half-open comparison makes
+        // sure vertices on the ray are only counted once
+        if (a.y > p.y) != (b.y > p.y) {
+            // is `p` left of the crossing? (multiplied out to avoid division)
+            let lhs = (p.x - a.x) * (b.y - a.y);
+            let rhs = (p.y - a.y) * (b.x - a.x);
+            if (b.y > a.y && lhs < rhs) || (b.y < a.y && lhs > rhs) {
+                inside = !inside;
+            }
+        }
+    }
+
+    if inside {
+        Containment::Inside
+    } else {
+        Containment::Outside
+    }
+}
+
+fn on_segment(a: Point, b: Point, p: Point) -> bool {
+    let cross = (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x);
+    cross == 0
+        && p.x >= a.x.min(b.x)
+        && p.x <= a.x.max(b.x)
+        && p.y >= a.y.min(b.y)
+        && p.y <= a.y.max(b.y)
+}
+
+/// All edges of the polygon, including the closing one
+fn edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
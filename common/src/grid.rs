@@ -1,6 +1,7 @@
 use std::{
     fmt::{Debug, Display},
-    ops::{Add, AddAssign, Sub},
+    hash::{Hash, Hasher},
+    ops::{Add, AddAssign, Index, IndexMut, Sub},
 };
 
 /// A point on a 2D coordinate system
@@ -69,3 +70,504 @@ where
         }
     }
 }
+
+/// A dense, fixed-size 2D grid, stored row by row
+///
+/// # Examples
+///
+/// ```
+/// use common::{Grid, GridCoord};
+///
+/// let grid = Grid::parse("123\n456\n", |c| c.to_digit(10).unwrap());
+///
+/// assert_eq!(3, grid.width());
+/// assert_eq!(2, grid.height());
+/// assert_eq!(6, grid[GridCoord::new(2, 1)]);
+/// assert_eq!(&[4, 5, 6], grid.row(1));
+/// assert_eq!(None, grid.get(GridCoord::new(3, 0)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Create a `width` by `height` grid with every cell set to `fill`
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Create a grid from its cells, row by row
+    ///
+    /// A width of 0 is only allowed without cells, and gives an empty grid.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        if width == 0 {
+            assert!(
+                cells.is_empty(),
+                "{} cells do not fit rows of width 0",
+                cells.len()
+            );
+            return Self {
+                width: 0,
+                height: 0,
+                cells,
+            };
+        }
+        assert!(
+            cells.len().is_multiple_of(width),
+            "{} cells do not fit rows of width {width}",
+            cells.len()
+        );
+
+        Self {
+            width,
+            height: cells.len() / width,
+            cells,
+        }
+    }
+
+    /// Parse a grid from lines of characters, converting each with `f`
+    ///
+    /// Blank lines are skipped; input without any other lines gives an empty grid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows do not all have the same length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::Grid;
+    ///
+    /// let grid = Grid::parse("\n#.\n.#\n", |c| c == '#');
+    /// assert_eq!((2, 2), (grid.width(), grid.height()));
+    ///
+    /// let empty = Grid::parse("", |c| c == '#');
+    /// assert_eq!((0, 0), (empty.width(), empty.height()));
+    /// ```
+    ///
+    /// ```should_panic
+    /// use common::Grid;
+    ///
+    /// // the second row is too short
+    /// Grid::parse("ab\nc\nd\n", |c| c);
+    /// ```
+    pub fn parse<F>(input: &str, mut f: F) -> Self
+    where
+        F: FnMut(char) -> T,
+    {
+        let width = input
+            .lines()
+            .find(|l| !l.is_empty())
+            .map_or(0, |l| l.chars().count());
+        let mut cells = Vec::new();
+        for (i, line) in input.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+            let len = line.chars().count();
+            assert_eq!(
+                width,
+                len,
+                "line {} has {len} cells instead of {width}",
+                i + 1
+            );
+            cells.extend(line.chars().map(&mut f));
+        }
+
+        Self::from_vec(width, cells)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether `pos` lies within the grid
+    pub fn in_bounds(&self, pos: GridCoord<usize>) -> bool {
+        pos.x < self.width && pos.y < self.height
+    }
+
+    /// Position of the cell at `index` in [`Self::cells`]
+    pub fn coord_of(&self, index: usize) -> GridCoord<usize> {
+        GridCoord::new(index % self.width, index / self.width)
+    }
+
+    /// Index of the cell at `pos` in [`Self::cells`]
+    pub fn index_of(&self, pos: GridCoord<usize>) -> usize {
+        pos.y * self.width + pos.x
+    }
+
+    pub fn get(&self, pos: GridCoord<usize>) -> Option<&T> {
+        self.in_bounds(pos).then(|| &self.cells[self.index_of(pos)])
+    }
+
+    pub fn get_mut(&mut self, pos: GridCoord<usize>) -> Option<&mut T> {
+        let idx = self.index_of(pos);
+        self.in_bounds(pos).then(|| &mut self.cells[idx])
+    }
+
+    /// All cells, row by row
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> std::slice::Chunks<'_, T> {
+        self.cells.chunks(self.width)
+    }
+
+    /// Iterate over all cells with their position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (GridCoord<usize>, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (self.coord_of(i), c))
+    }
+
+    /// Iterate over all positions, row by row
+    pub fn coords(&self) -> impl Iterator<Item = GridCoord<usize>> + '_ {
+        (0..self.cells.len()).map(|i| self.coord_of(i))
+    }
+
+    /// The up to four orthogonal neighbours of `pos` that are within the grid
+    pub fn neighbors4(&self, pos: GridCoord<usize>) -> impl Iterator<Item = GridCoord<usize>> + '_ {
+        self.neighbors(pos, &NEIGHBORS4)
+    }
+
+    /// The up to eight orthogonal and diagonal neighbours of `pos` that are within the grid
+    pub fn neighbors8(&self, pos: GridCoord<usize>) -> impl Iterator<Item = GridCoord<usize>> + '_ {
+        self.neighbors(pos, &NEIGHBORS8)
+    }
+
+    fn neighbors<'a>(
+        &'a self,
+        pos: GridCoord<usize>,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = GridCoord<usize>> + 'a {
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let p = GridCoord::new(pos.x.checked_add_signed(dx)?, pos.y.checked_add_signed(dy)?);
+            self.in_bounds(p).then_some(p)
+        })
+    }
+
+    /// Create a new grid of the same size by converting every cell
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Render the grid, converting each cell with `to_char`. Each row is terminated by a newline.
+    pub fn render<F>(&self, mut to_char: F) -> String
+    where
+        F: FnMut(&T) -> char,
+    {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            out.extend(row.iter().map(&mut to_char));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Transformations; these all return a new grid
+impl<T: Clone> Grid<T> {
+    /// Swap rows and columns
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::Grid;
+    ///
+    /// let g = Grid::parse("ab\ncd\nef\n", |c| c);
+    /// assert_eq!("ace\nbdf\n", g.transpose().to_string());
+    /// ```
+    pub fn transpose(&self) -> Self {
+        self.remap(self.height, self.width, |x, y| GridCoord::new(y, x))
+    }
+
+    /// Rotate clockwise by 90 degrees
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::Grid;
+    ///
+    /// let g = Grid::parse("ab\ncd\nef\n", |c| c);
+    /// assert_eq!("eca\nfdb\n", g.rotate_cw().to_string());
+    /// assert_eq!("bdf\nace\n", g.rotate_ccw().to_string());
+    /// assert_eq!(g, g.rotate_cw().rotate_ccw());
+    /// ```
+    pub fn rotate_cw(&self) -> Self {
+        let h = self.height;
+        self.remap(self.height, self.width, |x, y| GridCoord::new(y, h - 1 - x))
+    }
+
+    /// Rotate counter-clockwise by 90 degrees
+    pub fn rotate_ccw(&self) -> Self {
+        let w = self.width;
+        self.remap(self.height, self.width, |x, y| GridCoord::new(w - 1 - y, x))
+    }
+
+    /// Mirror left to right
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::Grid;
+    ///
+    /// let g = Grid::parse("ab\ncd\n", |c| c);
+    /// assert_eq!("ba\ndc\n", g.flip_h().to_string());
+    /// assert_eq!("cd\nab\n", g.flip_v().to_string());
+    /// ```
+    pub fn flip_h(&self) -> Self {
+        let w = self.width;
+        self.remap(self.width, self.height, |x, y| GridCoord::new(w - 1 - x, y))
+    }
+
+    /// Mirror top to bottom
+    pub fn flip_v(&self) -> Self {
+        let h = self.height;
+        self.remap(self.width, self.height, |x, y| GridCoord::new(x, h - 1 - y))
+    }
+
+    /// Fold the part below row `y` up onto the part above it
+    ///
+    /// Row `y` itself is dropped. Cells that end up on top of each other are combined with
+    /// `merge(upper, lower)`. If one part is taller, the result is as tall as that part.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::Grid;
+    ///
+    /// let g = Grid::parse("#..\n...\n---\n..#\n#..\n", |c| c == '#');
+    /// let folded = g.fold_up(2, |&a, &b| a || b);
+    ///
+    /// assert_eq!("#..\n..#\n", folded.render(|&b| if b { '#' } else { '.' }));
+    /// ```
+    pub fn fold_up<F>(&self, y: usize, mut merge: F) -> Self
+    where
+        F: FnMut(&T, &T) -> T,
+    {
+        assert!(y < self.height, "fold line {y} outside of grid");
+
+        let n = y.max(self.height - y - 1);
+        let mut cells = Vec::with_capacity(n * self.width);
+        for i in 0..n {
+            let upper = (y + i).checked_sub(n).map(|r| self.row(r));
+            let lower = Some(y + n - i)
+                .filter(|&r| r < self.height)
+                .map(|r| self.row(r));
+
+            match (upper, lower) {
+                (Some(u), Some(l)) => cells.extend(u.iter().zip(l).map(|(a, b)| merge(a, b))),
+                (Some(r), None) | (None, Some(r)) => cells.extend_from_slice(r),
+                (None, None) => unreachable!(),
+            }
+        }
+
+        Self {
+            width: self.width,
+            height: n,
+            cells,
+        }
+    }
+
+    /// Fold the part right of column `x` onto the part left of it; see [`Self::fold_up`]
+    pub fn fold_left<F>(&self, x: usize, merge: F) -> Self
+    where
+        F: FnMut(&T, &T) -> T,
+    {
+        self.transpose().fold_up(x, merge).transpose()
+    }
+
+    /// Build a `width` by `height` grid where each cell is taken from `source(x, y)`
+    fn remap<F>(&self, width: usize, height: usize, source: F) -> Self
+    where
+        F: Fn(usize, usize) -> GridCoord<usize>,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| self[source(x, y)].clone())
+            .collect();
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+}
+
+/// Comparisons, e.g. for finding lines of reflection
+impl<T: PartialEq> Grid<T> {
+    /// Number of cells that differ between rows `a` and `b`
+    pub fn row_diff(&self, a: usize, b: usize) -> usize {
+        self.row(a)
+            .iter()
+            .zip(self.row(b))
+            .filter(|(x, y)| x != y)
+            .count()
+    }
+
+    /// Number of cells that differ between columns `a` and `b`
+    pub fn col_diff(&self, a: usize, b: usize) -> usize {
+        (0..self.height)
+            .filter(|&y| self[GridCoord::new(a, y)] != self[GridCoord::new(b, y)])
+            .count()
+    }
+
+    /// Number of mismatching cells when mirroring the grid on the line between rows `y - 1` and
+    /// `y`, ignoring rows that have no counterpart
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `1 <= y < height`, i.e. unless the line lies strictly inside the grid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::Grid;
+    ///
+    /// let input = [
+    ///     "#...##..#",
+    ///     "#....#..#",
+    ///     "..##..###",
+    ///     "#####.##.",
+    ///     "#####.##.",
+    ///     "..##..###",
+    ///     "#....#..#",
+    /// ];
+    /// let g = Grid::parse(&input.join("\n"), |c| c);
+    ///
+    /// assert_eq!(0, g.row_reflection_diff(4));
+    /// // "smudged" reflection, with a single mismatch
+    /// assert_eq!(1, g.row_reflection_diff(1));
+    /// assert_eq!(None, (1..g.width()).find(|&x| g.col_reflection_diff(x) == 0));
+    /// ```
+    pub fn row_reflection_diff(&self, y: usize) -> usize {
+        assert!(
+            (1..self.height).contains(&y),
+            "row reflection line {y} outside of 1..{}",
+            self.height
+        );
+        (0..y.min(self.height - y))
+            .map(|i| self.row_diff(y - 1 - i, y + i))
+            .sum()
+    }
+
+    /// Like [`Self::row_reflection_diff`], for the line between columns `x - 1` and `x`
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `1 <= x < width`.
+    pub fn col_reflection_diff(&self, x: usize) -> usize {
+        assert!(
+            (1..self.width).contains(&x),
+            "column reflection line {x} outside of 1..{}",
+            self.width
+        );
+        (0..x.min(self.width - x))
+            .map(|i| self.col_diff(x - 1 - i, x + i))
+            .sum()
+    }
+}
+
+impl<T: Hash> Grid<T> {
+    /// A cheap hash of the grid contents that is stable across runs
+    ///
+    /// Useful as a key for cycle detection when storing every grid is too expensive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::Grid;
+    ///
+    /// let a = Grid::parse("O.#\n.O.\n", |c| c);
+    /// assert_eq!(a.fingerprint(), a.clone().fingerprint());
+    /// assert_ne!(a.fingerprint(), a.flip_h().fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = FnvHasher::default();
+        self.width.hash(&mut hasher);
+        self.cells.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// 64 bit FNV-1a; unlike `HashMap`'s default hasher it is not randomly seeded
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= u64::from(b);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+const NEIGHBORS4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const NEIGHBORS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+impl<T> Index<GridCoord<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: GridCoord<usize>) -> &Self::Output {
+        assert!(self.in_bounds(pos), "{pos:?} is out of bounds");
+        &self.cells[self.index_of(pos)]
+    }
+}
+
+impl<T> IndexMut<GridCoord<usize>> for Grid<T> {
+    fn index_mut(&mut self, pos: GridCoord<usize>) -> &mut Self::Output {
+        assert!(self.in_bounds(pos), "{pos:?} is out of bounds");
+        let idx = self.index_of(pos);
+        &mut self.cells[idx]
+    }
+}
+
+impl<T: Copy + Into<char>> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render(|&c| c.into()))
+    }
+}
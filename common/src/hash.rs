@@ -0,0 +1,274 @@
+//! MD5 hashing, and brute-force searches for hashes with a given shape
+//!
+//! MD5 is long broken for anything security related, but some puzzles are built around it.
+
+use std::{num::NonZeroUsize, thread};
+
+/// An MD5 digest
+pub type Digest = [u8; 16];
+
+const S: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9,
+    14, 20, 5, 9, 14, 20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 6, 10, 15,
+    21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+const K: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+/// Streaming MD5 hasher
+///
+/// Cloning a hasher after feeding it a common prefix is the cheap way to hash many messages that
+/// share that prefix: all complete 64 byte blocks of the prefix are only processed once.
+///
+/// # Examples
+///
+/// ```
+/// use common::hash::{to_hex, Md5};
+///
+/// let mut prefix = Md5::new();
+/// prefix.update(b"The quick brown fox ");
+///
+/// let mut h = prefix.clone();
+/// h.update(b"jumps over the lazy dog");
+/// assert_eq!("9e107d9d372bb6826bd81d3542a419d6", to_hex(&h.finalize()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Md5 {
+    state: [u32; 4],
+    buffer: [u8; 64],
+    buffered: usize,
+    len: u64,
+}
+
+impl Default for Md5 {
+    fn default() -> Self {
+        Self {
+            state: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476],
+            buffer: [0; 64],
+            buffered: 0,
+            len: 0,
+        }
+    }
+}
+
+impl Md5 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed more data into the hasher
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.len = self.len.wrapping_add(data.len() as u64);
+
+        if self.buffered > 0 {
+            let n = data.len().min(64 - self.buffered);
+            self.buffer[self.buffered..self.buffered + n].copy_from_slice(&data[..n]);
+            self.buffered += n;
+            data = &data[n..];
+            if self.buffered < 64 {
+                return;
+            }
+            let block = self.buffer;
+            self.compress(&block);
+            self.buffered = 0;
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            self.compress(block.try_into().unwrap());
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    /// Pad the message and return its digest
+    pub fn finalize(mut self) -> Digest {
+        let bit_len = self.len.wrapping_mul(8);
+
+        let mut padding = [0u8; 72];
+        padding[0] = 0x80;
+        // pad to 56 bytes modulo 64, leaving room for the length
+        let pad_len = if self.buffered < 56 {
+            56 - self.buffered
+        } else {
+            120 - self.buffered
+        };
+        padding[pad_len..pad_len + 8].copy_from_slice(&bit_len.to_le_bytes());
+
+        let len = self.len;
+        self.update(&padding[..pad_len + 8]);
+        self.len = len;
+        debug_assert_eq!(0, self.buffered);
+
+        let mut digest = [0; 16];
+        for (chunk, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+
+    fn compress(&mut self, block: &[u8; 64]) {
+        let mut m = [0u32; 16];
+        for (w, chunk) in m.iter_mut().zip(block.chunks_exact(4)) {
+            *w = u32::from_le_bytes(chunk.try_into().unwrap());
+        }
+
+        let [mut a, mut b, mut c, mut d] = self.state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let f = f.wrapping_add(a).wrapping_add(K[i]).wrapping_add(m[g]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(f.rotate_left(S[i]));
+        }
+
+        for (s, v) in self.state.iter_mut().zip([a, b, c, d]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+}
+
+/// The MD5 digest of `data`
+///
+/// # Examples
+///
+/// ```
+/// use common::hash::{md5, to_hex};
+///
+/// assert_eq!("d41d8cd98f00b204e9800998ecf8427e", to_hex(&md5(b"")));
+/// assert_eq!("000001dbbfa3a5c83a2d506429c7b00e", to_hex(&md5(b"abcdef609043")));
+/// ```
+pub fn md5(data: &[u8]) -> Digest {
+    let mut h = Md5::new();
+    h.update(data);
+    h.finalize()
+}
+
+/// Lowercase hex representation of a digest
+pub fn to_hex(digest: &Digest) -> String {
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Number of leading zeros in the hex representation of a digest
+///
+/// # Examples
+///
+/// ```
+/// use common::hash::{leading_zero_nibbles, md5};
+///
+/// assert_eq!(5, leading_zero_nibbles(&md5(b"pqrstuv1048970")));
+/// ```
+pub fn leading_zero_nibbles(digest: &Digest) -> u32 {
+    let zero_bits: u32 = digest
+        .iter()
+        .position(|&b| b != 0)
+        .map_or(128, |i| i as u32 * 8 + digest[i].leading_zeros());
+    zero_bits / 4
+}
+
+/// Find the lowest nonce `n >= start` for which the digest of `prefix` followed by `n` in decimal
+/// satisfies `accept`
+///
+/// The search is spread over all available cores, but always returns the lowest match. It does
+/// not return if there is no match.
+///
+/// # Examples
+///
+/// ```
+/// use common::hash::{leading_zero_nibbles, lowest_nonce};
+///
+/// let first = lowest_nonce(b"abc", 0, |d| leading_zero_nibbles(d) >= 3);
+/// let second = lowest_nonce(b"abc", first + 1, |d| leading_zero_nibbles(d) >= 3);
+/// assert_eq!((2196, 3527), (first, second));
+/// ```
+pub fn lowest_nonce<F>(prefix: &[u8], start: u64, accept: F) -> u64
+where
+    F: Fn(&Digest) -> bool + Sync,
+{
+    const CHUNK: u64 = 1 << 14;
+
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get) as u64;
+    let mut base = Md5::new();
+    base.update(prefix);
+
+    let search = |from: u64| {
+        (from..from + CHUNK).find(|&n| {
+            let mut digits = [0; 20];
+            let mut h = base.clone();
+            h.update(format_decimal(n, &mut digits));
+            accept(&h.finalize())
+        })
+    };
+
+    // every round checks `threads` consecutive chunks; the earliest chunk with a match wins
+    let mut from = start;
+    loop {
+        let found = thread::scope(|s| {
+            let handles: Vec<_> = (0..threads)
+                .map(|t| {
+                    let search = &search;
+                    s.spawn(move || search(from + t * CHUNK))
+                })
+                .collect();
+            handles.into_iter().filter_map(|h| h.join().unwrap()).min()
+        });
+        if let Some(n) = found {
+            return n;
+        }
+        from += threads * CHUNK;
+    }
+}
+
+/// Find the lowest positive nonce whose digest, appended to `prefix`, starts with `zeros` zeros
+/// in hex
+///
+/// # Panics
+///
+/// Panics if `zeros` is more than 32, the number of hex digits in an MD5 digest.
+///
+/// # Examples
+///
+/// ```
+/// use common::hash::find_nonce;
+///
+/// assert_eq!(609043, find_nonce("abcdef", 5));
+/// ```
+pub fn find_nonce(prefix: &str, zeros: u32) -> u64 {
+    assert!(
+        zeros <= 32,
+        "an MD5 digest has only 32 hex digits, not {zeros}"
+    );
+    // checking whole bytes first avoids computing the exact count for almost every digest
+    let bytes = (zeros / 2) as usize;
+    lowest_nonce(prefix.as_bytes(), 1, |d| {
+        d[..bytes].iter().all(|&b| b == 0) && (zeros.is_multiple_of(2) || d[bytes] >> 4 == 0)
+    })
+}
+
+fn format_decimal(mut n: u64, buf: &mut [u8; 20]) -> &[u8] {
+    let mut i = buf.len();
+    loop {
+        i -= 1;
+        buf[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            return &buf[i..];
+        }
+    }
+}
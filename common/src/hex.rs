@@ -0,0 +1,255 @@
+//! Coordinates on a hexagonal grid
+//!
+//! Hexes are stored in axial form `(q, r)`; the third cube coordinate `s = -q - r` is derived on
+//! demand. The same axial coordinates work for both flat-topped and pointy-topped grids, only the
+//! names of the directions and the offset layout used for rendering differ.
+
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, Neg, Sub},
+};
+
+use crate::GridCoord;
+
+/// Which way the hexes are turned
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum Orientation {
+    /// Flat sides at the top and bottom; moves are `n`, `ne`, `se`, `s`, `sw` and `nw`
+    FlatTop,
+    /// Corners at the top and bottom; moves are `ne`, `e`, `se`, `sw`, `w` and `nw`
+    PointyTop,
+}
+
+impl Orientation {
+    /// Names of the six directions, clockwise, matching [`HexCoord::DIRECTIONS`]
+    pub fn names(self) -> [&'static str; 6] {
+        match self {
+            Orientation::FlatTop => ["ne", "se", "s", "sw", "nw", "n"],
+            Orientation::PointyTop => ["ne", "e", "se", "sw", "w", "nw"],
+        }
+    }
+
+    /// The unit step for the direction called `name`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::hex::{HexCoord, Orientation};
+    ///
+    /// assert_eq!(Some(HexCoord::new(0, -1)), Orientation::FlatTop.direction("n"));
+    /// assert_eq!(Some(HexCoord::new(0, -1)), Orientation::PointyTop.direction("nw"));
+    /// assert_eq!(None, Orientation::PointyTop.direction("n"));
+    /// ```
+    pub fn direction(self, name: &str) -> Option<HexCoord> {
+        let i = self.names().iter().position(|&n| n == name)?;
+        Some(HexCoord::DIRECTIONS[i])
+    }
+}
+
+/// A hex in axial coordinates
+///
+/// # Examples
+///
+/// ```
+/// use common::hex::{HexCoord, Orientation};
+///
+/// // 2017 day 11
+/// let end = HexCoord::walk("se,sw,se,sw,sw", Orientation::FlatTop);
+/// assert_eq!(3, end.distance(HexCoord::ORIGIN));
+///
+/// // 2020 day 24
+/// let tile = HexCoord::walk("nwwswee", Orientation::PointyTop);
+/// assert_eq!(HexCoord::ORIGIN, tile);
+/// ```
+#[derive(Debug, Default, Hash, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub struct HexCoord {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl HexCoord {
+    pub const ORIGIN: Self = Self::new(0, 0);
+
+    /// The six unit steps, clockwise; see [`Orientation::names`] for what they are called
+    pub const DIRECTIONS: [Self; 6] = [
+        Self::new(1, -1),
+        Self::new(1, 0),
+        Self::new(0, 1),
+        Self::new(-1, 1),
+        Self::new(-1, 0),
+        Self::new(0, -1),
+    ];
+
+    pub const fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    /// The third cube coordinate
+    pub const fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// Parse a string of moves into unit steps
+    ///
+    /// Moves may be separated by commas or whitespace, or not separated at all as in `nwwswee`.
+    ///
+    /// # Panics
+    ///
+    /// Panics on moves that do not exist for `orientation`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::hex::{HexCoord, Orientation};
+    ///
+    /// let steps: Vec<_> = HexCoord::moves("ne,s", Orientation::FlatTop).collect();
+    /// assert_eq!(vec![HexCoord::new(1, -1), HexCoord::new(0, 1)], steps);
+    /// ```
+    pub fn moves(path: &str, orientation: Orientation) -> impl Iterator<Item = Self> + '_ {
+        let mut rest = path;
+        std::iter::from_fn(move || {
+            rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+            if rest.is_empty() {
+                return None;
+            }
+
+            // `n` and `s` are the only letters that can start a two letter move
+            let len = match rest.as_bytes() {
+                [b'n' | b's', b'e' | b'w', ..] => 2,
+                _ => 1,
+            };
+            let (name, tail) = rest.split_at(len);
+            rest = tail;
+            Some(
+                orientation
+                    .direction(name)
+                    .unwrap_or_else(|| panic!("unknown direction {name:?} for {orientation:?}")),
+            )
+        })
+    }
+
+    /// Follow a string of moves from the origin; see [`Self::moves`]
+    pub fn walk(path: &str, orientation: Orientation) -> Self {
+        Self::moves(path, orientation).fold(Self::ORIGIN, |pos, step| pos + step)
+    }
+
+    /// The six adjacent hexes, clockwise
+    pub fn neighbors(self) -> [Self; 6] {
+        Self::DIRECTIONS.map(|d| self + d)
+    }
+
+    /// Number of steps between two hexes
+    pub fn distance(self, other: Self) -> u32 {
+        let d = self - other;
+        (d.q.unsigned_abs() + d.r.unsigned_abs() + d.s().unsigned_abs()) / 2
+    }
+
+    /// All hexes at exactly `radius` steps, walking clockwise
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::hex::HexCoord;
+    ///
+    /// let c = HexCoord::new(2, -1);
+    /// assert_eq!(vec![c], c.ring(0));
+    /// assert_eq!(12, c.ring(2).len());
+    /// assert!(c.ring(2).iter().all(|&h| h.distance(c) == 2));
+    /// ```
+    pub fn ring(self, radius: u32) -> Vec<Self> {
+        if radius == 0 {
+            return vec![self];
+        }
+
+        // start in one corner, then walk along each of the six sides
+        let mut pos = self + Self::DIRECTIONS[4] * radius as i32;
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+        for dir in Self::DIRECTIONS {
+            for _ in 0..radius {
+                ring.push(pos);
+                pos += dir;
+            }
+        }
+        ring
+    }
+
+    /// All hexes within `radius` steps, ring by ring from the centre outwards
+    pub fn within(self, radius: u32) -> impl Iterator<Item = Self> {
+        (0..=radius).flat_map(move |r| self.ring(r))
+    }
+
+    /// Position in a rectangular layout for rendering
+    ///
+    /// Flat-topped grids shift every odd column down by half a hex ("odd-q"), pointy-topped grids
+    /// shift every odd row right by half a hex ("odd-r").
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::{hex::{HexCoord, Orientation}, GridCoord};
+    ///
+    /// let h = HexCoord::new(-3, 2);
+    /// assert_eq!(GridCoord::new(-3, 0), h.to_offset(Orientation::FlatTop));
+    /// assert_eq!(GridCoord::new(-2, 2), h.to_offset(Orientation::PointyTop));
+    /// assert_eq!(h, HexCoord::from_offset(GridCoord::new(-2, 2), Orientation::PointyTop));
+    /// ```
+    pub fn to_offset(self, orientation: Orientation) -> GridCoord<i32> {
+        match orientation {
+            Orientation::FlatTop => GridCoord::new(self.q, self.r + (self.q - (self.q & 1)) / 2),
+            Orientation::PointyTop => GridCoord::new(self.q + (self.r - (self.r & 1)) / 2, self.r),
+        }
+    }
+
+    /// Inverse of [`Self::to_offset`]
+    pub fn from_offset(pos: GridCoord<i32>, orientation: Orientation) -> Self {
+        let GridCoord { x, y } = pos;
+        match orientation {
+            Orientation::FlatTop => Self::new(x, y - (x - (x & 1)) / 2),
+            Orientation::PointyTop => Self::new(x - (y - (y & 1)) / 2, y),
+        }
+    }
+}
+
+impl Display for HexCoord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{},{})", self.q, self.r, self.s())
+    }
+}
+
+impl Add for HexCoord {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl AddAssign for HexCoord {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for HexCoord {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Neg for HexCoord {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.q, -self.r)
+    }
+}
+
+impl Mul<i32> for HexCoord {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Self::new(self.q * rhs, self.r * rhs)
+    }
+}
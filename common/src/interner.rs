@@ -0,0 +1,261 @@
+//! Dense ids for string labels, and graphs built on top of them
+
+use std::{collections::HashMap, fmt::Debug, hash::Hash};
+
+/// Integer types usable as interned ids
+pub trait InternId: Copy + Eq + Hash + Debug {
+    /// Convert an index into an id; panics if it does not fit
+    fn from_index(index: usize) -> Self;
+    /// Convert the id back into an index
+    fn index(self) -> usize;
+}
+
+macro_rules! impl_intern_id {
+    ($($t:ty)*) => {$(
+        impl InternId for $t {
+            fn from_index(index: usize) -> Self {
+                Self::try_from(index)
+                    .unwrap_or_else(|_| panic!("too many labels for {}", stringify!($t)))
+            }
+
+            fn index(self) -> usize {
+                self as usize
+            }
+        }
+    )*};
+}
+
+impl_intern_id!(u8 u16 u32 usize);
+
+/// Maps string labels to dense ids `0..n`, in order of first appearance
+///
+/// # Examples
+///
+/// ```
+/// use common::interner::Interner;
+///
+/// let mut labels = Interner::<u16>::new();
+/// let aa = labels.intern("AA");
+/// let bb = labels.intern("BB");
+///
+/// assert_eq!((0, 1), (aa, bb));
+/// assert_eq!(aa, labels.intern("AA"));
+/// assert_eq!(Some(bb), labels.get("BB"));
+/// assert_eq!(None, labels.get("CC"));
+/// assert_eq!("BB", labels.resolve(bb));
+/// assert_eq!(2, labels.len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Interner<Id = u32> {
+    ids: HashMap<String, Id>,
+    labels: Vec<String>,
+}
+
+impl<Id> Default for Interner<Id> {
+    fn default() -> Self {
+        Self {
+            ids: HashMap::new(),
+            labels: Vec::new(),
+        }
+    }
+}
+
+impl<Id: InternId> Interner<Id> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id of `label`, assigning the next free one if it has not been seen before
+    pub fn intern(&mut self, label: &str) -> Id {
+        if let Some(&id) = self.ids.get(label) {
+            return id;
+        }
+
+        let id = Id::from_index(self.labels.len());
+        self.ids.insert(label.to_string(), id);
+        self.labels.push(label.to_string());
+        id
+    }
+
+    /// The id of `label`, if it has been interned
+    pub fn get(&self, label: &str) -> Option<Id> {
+        self.ids.get(label).copied()
+    }
+
+    /// The label for `id`
+    pub fn resolve(&self, id: Id) -> &str {
+        &self.labels[id.index()]
+    }
+
+    /// Number of distinct labels
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// All `(id, label)` pairs, ordered by id
+    pub fn iter(&self) -> impl Iterator<Item = (Id, &str)> {
+        self.labels
+            .iter()
+            .enumerate()
+            .map(|(i, l)| (Id::from_index(i), l.as_str()))
+    }
+}
+
+/// An adjacency-list graph whose nodes are identified by string labels
+///
+/// Neighbours keep the order in which their edges were added, which matters for inputs like
+/// `AAA = (BBB, CCC)` where left and right are distinct.
+///
+/// # Examples
+///
+/// ```
+/// use common::interner::LabeledGraph;
+///
+/// let g = LabeledGraph::<u16>::parse("start-A\nA-b\nA-end\n");
+/// let a = g.id("A").unwrap();
+///
+/// assert_eq!(4, g.len());
+/// assert_eq!(
+///     vec!["start", "b", "end"],
+///     g.neighbors(a).iter().map(|&n| g.label(n)).collect::<Vec<_>>(),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct LabeledGraph<Id = u32> {
+    labels: Interner<Id>,
+    edges: Vec<Vec<Id>>,
+}
+
+impl<Id> Default for LabeledGraph<Id> {
+    fn default() -> Self {
+        Self {
+            labels: Interner::default(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+impl<Id: InternId> LabeledGraph<Id> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a graph with one or more edges per line
+    ///
+    /// Supported formats are:
+    ///
+    /// - `a-b`: undirected edge between `a` and `b`
+    /// - `a -> b, c`: directed edges from `a` to `b` and `c`
+    /// - `a = (b, c)`: directed edges from `a` to `b` and `c`
+    ///
+    /// # Panics
+    ///
+    /// Panics on lines in any other format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::interner::LabeledGraph;
+    ///
+    /// let g = LabeledGraph::<u32>::parse("AAA = (BBB, CCC)\nbroadcaster -> a, b\n");
+    /// let aaa = g.id("AAA").unwrap();
+    /// let b = g.id("b").unwrap();
+    ///
+    /// assert_eq!(Some(aaa), g.id("AAA"));
+    /// assert_eq!(&[g.id("BBB").unwrap(), g.id("CCC").unwrap()], g.neighbors(aaa));
+    /// assert!(g.neighbors(b).is_empty());
+    /// ```
+    pub fn parse(input: &str) -> Self {
+        let mut graph = Self::new();
+        for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            graph.add_line(line);
+        }
+        graph
+    }
+
+    /// Add the edges described by a single line; see [`Self::parse`] for the formats
+    pub fn add_line(&mut self, line: &str) {
+        if let Some((from, to)) = line.split_once("->") {
+            for t in to.split(',') {
+                self.add_edge(from.trim(), t.trim());
+            }
+        } else if let Some((from, to)) = line.split_once('=') {
+            let to = to
+                .trim()
+                .strip_prefix('(')
+                .and_then(|t| t.strip_suffix(')'))
+                .unwrap_or_else(|| panic!("expected `a = (b, c)`, got {line:?}"));
+            for t in to.split(',') {
+                self.add_edge(from.trim(), t.trim());
+            }
+        } else if let Some((a, b)) = line.split_once('-') {
+            self.add_undirected_edge(a.trim(), b.trim());
+        } else {
+            panic!("unknown edge format: {line:?}");
+        }
+    }
+
+    /// Add a node without any edges, returning its id
+    pub fn add_node(&mut self, label: &str) -> Id {
+        let id = self.labels.intern(label);
+        if id.index() >= self.edges.len() {
+            self.edges.resize_with(id.index() + 1, Vec::new);
+        }
+        id
+    }
+
+    /// Add a directed edge from `from` to `to`
+    pub fn add_edge(&mut self, from: &str, to: &str) {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        self.edges[from.index()].push(to);
+    }
+
+    /// Add edges in both directions between `a` and `b`
+    pub fn add_undirected_edge(&mut self, a: &str, b: &str) {
+        self.add_edge(a, b);
+        self.add_edge(b, a);
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// The id of the node labelled `label`
+    pub fn id(&self, label: &str) -> Option<Id> {
+        self.labels.get(label)
+    }
+
+    /// The label of node `id`
+    pub fn label(&self, id: Id) -> &str {
+        self.labels.resolve(id)
+    }
+
+    /// Outgoing edges of node `id`, in the order they were added
+    pub fn neighbors(&self, id: Id) -> &[Id] {
+        &self.edges[id.index()]
+    }
+
+    /// All node ids
+    pub fn nodes(&self) -> impl Iterator<Item = Id> {
+        (0..self.len()).map(Id::from_index)
+    }
+
+    pub fn interner(&self) -> &Interner<Id> {
+        &self.labels
+    }
+}
+
+impl<Id: InternId> From<&str> for LabeledGraph<Id> {
+    fn from(input: &str) -> Self {
+        Self::parse(input)
+    }
+}
@@ -0,0 +1,410 @@
+use std::ops::{Range, RangeInclusive};
+
+use crate::num::Integer;
+
+/// A contiguous range of integers
+///
+/// Stored half-open (`start..end`); use [`Interval::inclusive`] and [`Interval::last`] to work
+/// with the inclusive convention instead.
+///
+/// # Examples
+///
+/// ```
+/// use common::Interval;
+///
+/// let a = Interval::new(2, 5);
+/// let b = Interval::inclusive(4, 8);
+///
+/// assert_eq!(3, a.len());
+/// assert_eq!(8, b.last());
+/// assert!(a.overlaps(&b));
+/// assert_eq!(Some(Interval::new(4, 5)), a.intersection(&b));
+/// assert_eq!(Some(Interval::new(2, 9)), a.union(&b));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Integer> Interval<T> {
+    /// Create the half-open interval `start..end`
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    /// Create the inclusive interval `first..=last`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `last` is `T::MAX`, as the half-open end would not fit into `T`.
+    pub fn inclusive(first: T, last: T) -> Self {
+        let end = last
+            .checked_add(T::ONE)
+            .unwrap_or_else(|| panic!("inclusive interval ending at {last} overflows"));
+        Self::new(first, end)
+    }
+
+    /// Create an interval of `len` elements starting at `start`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the end of the interval does not fit into `T`.
+    pub fn with_len(start: T, len: T) -> Self {
+        let end = start
+            .checked_add(len)
+            .unwrap_or_else(|| panic!("interval of {len} elements from {start} overflows"));
+        Self::new(start, end)
+    }
+
+    /// The last element in the interval (the inclusive end)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interval is empty.
+    pub fn last(&self) -> T {
+        assert!(
+            !self.is_empty(),
+            "empty interval {self:?} has no last element"
+        );
+        self.end - T::ONE
+    }
+
+    /// Whether the interval contains no elements
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// Number of elements in the interval
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::ZERO
+        } else {
+            self.end - self.start
+        }
+    }
+
+    /// Whether `x` lies within the interval
+    pub fn contains(&self, x: T) -> bool {
+        self.start <= x && x < self.end
+    }
+
+    /// Whether `other` lies completely within the interval
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    /// Whether the two intervals share at least one element
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start.max(other.start) < self.end.min(other.end)
+    }
+
+    /// Whether the two intervals overlap or are directly adjacent
+    pub fn touches(&self, other: &Self) -> bool {
+        self.start.max(other.start) <= self.end.min(other.end)
+    }
+
+    /// Elements contained in both intervals
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let i = Self::new(self.start.max(other.start), self.end.min(other.end));
+        (!i.is_empty()).then_some(i)
+    }
+
+    /// The merged interval, if the two intervals overlap or are adjacent
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        self.touches(other)
+            .then(|| Self::new(self.start.min(other.start), self.end.max(other.end)))
+    }
+
+    /// Elements in `self` but not in `other`, as the parts left and right of `other`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::Interval;
+    ///
+    /// let a = Interval::new(0, 10);
+    /// assert_eq!(
+    ///     (Some(Interval::new(0, 3)), Some(Interval::new(5, 10))),
+    ///     a.difference(&Interval::new(3, 5)),
+    /// );
+    /// assert_eq!((None, None), a.difference(&Interval::new(-5, 15)));
+    /// assert_eq!((Some(a), None), a.difference(&Interval::new(12, 15)));
+    /// assert_eq!((None, Some(a)), a.difference(&Interval::new(-5, -2)));
+    /// ```
+    pub fn difference(&self, other: &Self) -> (Option<Self>, Option<Self>) {
+        if !self.overlaps(other) {
+            let rest = Some(*self).filter(|i| !i.is_empty());
+            return if self.end <= other.start {
+                (rest, None)
+            } else {
+                (None, rest)
+            };
+        }
+
+        let left = Self::new(self.start, other.start);
+        let right = Self::new(other.end, self.end);
+        (
+            (!left.is_empty()).then_some(left),
+            (!right.is_empty()).then_some(right),
+        )
+    }
+
+    /// Split the interval into the elements before `at` and the elements from `at` onwards
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::Interval;
+    ///
+    /// let a = Interval::inclusive(1, 4000);
+    /// assert_eq!(
+    ///     (Some(Interval::new(1, 1351)), Some(Interval::new(1351, 4001))),
+    ///     a.split_at(1351),
+    /// );
+    /// assert_eq!((None, Some(a)), a.split_at(0));
+    /// ```
+    pub fn split_at(&self, at: T) -> (Option<Self>, Option<Self>) {
+        let left = Self::new(self.start, at.min(self.end));
+        let right = Self::new(at.max(self.start), self.end);
+        (
+            (!left.is_empty()).then_some(left),
+            (!right.is_empty()).then_some(right),
+        )
+    }
+
+    /// Move the interval by `offset`
+    ///
+    /// # Panics
+    ///
+    /// Panics if either end of the moved interval does not fit into `T`.
+    pub fn shift(&self, offset: T) -> Self {
+        match (self.start.checked_add(offset), self.end.checked_add(offset)) {
+            (Some(start), Some(end)) => Self::new(start, end),
+            _ => panic!("shifting {self:?} by {offset} overflows"),
+        }
+    }
+}
+
+impl<T: Integer> From<Range<T>> for Interval<T> {
+    fn from(r: Range<T>) -> Self {
+        Self::new(r.start, r.end)
+    }
+}
+
+impl<T: Integer> From<RangeInclusive<T>> for Interval<T> {
+    fn from(r: RangeInclusive<T>) -> Self {
+        let (first, last) = r.into_inner();
+        Self::inclusive(first, last)
+    }
+}
+
+impl<T: Integer> From<Interval<T>> for Range<T> {
+    fn from(i: Interval<T>) -> Self {
+        i.start..i.end
+    }
+}
+
+/// A set of integers, stored as sorted, disjoint, non-adjacent [`Interval`]s
+///
+/// # Examples
+///
+/// ```
+/// use common::{Interval, IntervalSet};
+///
+/// let mut set = IntervalSet::new();
+/// set.insert(Interval::inclusive(10, 14));
+/// set.insert(Interval::inclusive(3, 5));
+/// set.insert(Interval::inclusive(12, 18));
+/// set.insert(Interval::inclusive(16, 20));
+///
+/// assert_eq!(
+///     vec![Interval::inclusive(3, 5), Interval::inclusive(10, 20)],
+///     set.iter().copied().collect::<Vec<_>>(),
+/// );
+/// assert_eq!(14, set.total_len());
+/// assert_eq!(vec![Interval::new(6, 10)], set.gaps().collect::<Vec<_>>());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Integer> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Integer> IntervalSet<T> {
+    /// Create a new, empty set
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Number of disjoint intervals in the set
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// Whether the set contains no elements
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Total number of elements in the set
+    pub fn total_len(&self) -> T {
+        self.intervals.iter().fold(T::ZERO, |acc, i| acc + i.len())
+    }
+
+    /// Iterate over the disjoint intervals, in ascending order
+    pub fn iter(&self) -> std::slice::Iter<'_, Interval<T>> {
+        self.intervals.iter()
+    }
+
+    /// The smallest element in the set
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    /// The largest element in the set
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().map(|i| i.last())
+    }
+
+    /// Add all elements of `interval` to the set
+    pub fn insert(&mut self, interval: impl Into<Interval<T>>) {
+        let mut new = interval.into();
+        if new.is_empty() {
+            return;
+        }
+
+        // all intervals that touch `new` are contiguous in the sorted list
+        let from = self.intervals.partition_point(|i| i.end < new.start);
+        let to = self.intervals.partition_point(|i| i.start <= new.end);
+        for i in &self.intervals[from..to] {
+            new = new.union(i).expect("touching intervals");
+        }
+        self.intervals.splice(from..to, [new]);
+    }
+
+    /// Remove all elements of `interval` from the set
+    pub fn remove(&mut self, interval: impl Into<Interval<T>>) {
+        let rm = interval.into();
+        if rm.is_empty() {
+            return;
+        }
+
+        let from = self.intervals.partition_point(|i| i.end <= rm.start);
+        let to = self.intervals.partition_point(|i| i.start < rm.end);
+        let remaining = self.intervals[from..to]
+            .iter()
+            .flat_map(|i| {
+                let (l, r) = i.difference(&rm);
+                [l, r]
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+        self.intervals.splice(from..to, remaining);
+    }
+
+    /// Whether `x` is in the set
+    pub fn contains(&self, x: T) -> bool {
+        let idx = self.intervals.partition_point(|i| i.end <= x);
+        self.intervals.get(idx).is_some_and(|i| i.contains(x))
+    }
+
+    /// Whether all elements of `interval` are in the set
+    pub fn contains_interval(&self, interval: &Interval<T>) -> bool {
+        if interval.is_empty() {
+            return true;
+        }
+        let idx = self.intervals.partition_point(|i| i.end <= interval.start);
+        self.intervals
+            .get(idx)
+            .is_some_and(|i| i.contains_interval(interval))
+    }
+
+    /// Elements in either set
+    pub fn union(&self, other: &Self) -> Self {
+        let mut set = self.clone();
+        for &i in &other.intervals {
+            set.insert(i);
+        }
+        set
+    }
+
+    /// Elements in both sets
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::{Interval, IntervalSet};
+    ///
+    /// let a = IntervalSet::from_iter([0..5, 10..15]);
+    /// let b = IntervalSet::from_iter([3..12]);
+    ///
+    /// assert_eq!(IntervalSet::from_iter([3..5, 10..12]), a.intersection(&b));
+    /// assert_eq!(IntervalSet::from_iter([0..3, 12..15]), a.difference(&b));
+    /// assert_eq!(IntervalSet::from_iter([0..15]), a.union(&b));
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut a, mut b) = (0, 0);
+        while a < self.intervals.len() && b < other.intervals.len() {
+            let (x, y) = (&self.intervals[a], &other.intervals[b]);
+            if let Some(i) = x.intersection(y) {
+                intervals.push(i);
+            }
+            if x.end < y.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+        Self { intervals }
+    }
+
+    /// Elements in `self` but not in `other`
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut set = self.clone();
+        for &i in &other.intervals {
+            set.remove(i);
+        }
+        set
+    }
+
+    /// The elements between the smallest and largest element that are not in the set
+    pub fn gaps(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.intervals
+            .windows(2)
+            .map(|w| Interval::new(w[0].end, w[1].start))
+    }
+}
+
+impl<T: Integer, I: Into<Interval<T>>> FromIterator<I> for IntervalSet<T> {
+    fn from_iter<It: IntoIterator<Item = I>>(iter: It) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Integer, I: Into<Interval<T>>> Extend<I> for IntervalSet<T> {
+    fn extend<It: IntoIterator<Item = I>>(&mut self, iter: It) {
+        for i in iter {
+            self.insert(i);
+        }
+    }
+}
+
+impl<'a, T: Integer> IntoIterator for &'a IntervalSet<T> {
+    type Item = &'a Interval<T>;
+    type IntoIter = std::slice::Iter<'a, Interval<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
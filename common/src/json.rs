@@ -0,0 +1,491 @@
+//! A small JSON parser
+//!
+//! Parses a document into a [`Value`] tree, which can then be walked with [`Value::visit`] or
+//! reduced with [`Value::fold`].
+
+use std::{error::Error, fmt, str::FromStr};
+
+/// A JSON value
+///
+/// Objects keep their keys in document order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Value::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Value::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// The number, if it is an integer that fits into an `i64`
+    pub fn as_i64(&self) -> Option<i64> {
+        let n = self.as_f64()?;
+        (n.fract() == 0.0 && n >= i64::MIN as f64 && n <= i64::MAX as f64).then_some(n as i64)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Value::Object(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    /// The value stored under `key`, if this is an object containing it
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_object()?
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+
+    /// The direct children of an array or object
+    pub fn children(&self) -> Box<dyn Iterator<Item = &Value> + '_> {
+        match self {
+            Value::Array(a) => Box::new(a.iter()),
+            Value::Object(o) => Box::new(o.iter().map(|(_, v)| v)),
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+
+    /// Walk the tree depth first, calling `f` on every value before its children
+    ///
+    /// Returning `false` from `f` skips the children of that value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::json::Value;
+    ///
+    /// let doc: Value = r#"{"a": [1, 2], "b": {"c": 3}}"#.parse().unwrap();
+    /// let mut sum = 0;
+    /// doc.visit(&mut |v| {
+    ///     sum += v.as_i64().unwrap_or(0);
+    ///     v.get("c").is_none()
+    /// });
+    /// assert_eq!(3, sum);
+    /// ```
+    pub fn visit<F>(&self, f: &mut F)
+    where
+        F: FnMut(&Value) -> bool,
+    {
+        if f(self) {
+            for child in self.children() {
+                child.visit(f);
+            }
+        }
+    }
+
+    /// Reduce the tree bottom up: `f` is called with each value and the results for its children
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::json::Value;
+    ///
+    /// // 2015 day 12: sum all numbers, ignoring objects with a "red" property
+    /// let doc: Value = r#"[1,{"c":"red","b":2},3]"#.parse().unwrap();
+    /// let sum = doc.fold(&mut |v, children: Vec<i64>| match v {
+    ///     Value::Number(_) => v.as_i64().unwrap(),
+    ///     Value::Object(o) if o.iter().any(|(_, v)| v.as_str() == Some("red")) => 0,
+    ///     _ => children.into_iter().sum(),
+    /// });
+    /// assert_eq!(4, sum);
+    /// ```
+    pub fn fold<B, F>(&self, f: &mut F) -> B
+    where
+        F: FnMut(&Value, Vec<B>) -> B,
+    {
+        let children = self.children().map(|c| c.fold(f)).collect();
+        f(self, children)
+    }
+}
+
+impl FromStr for Value {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
+
+impl fmt::Display for Value {
+    /// Compact JSON, without any whitespace
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Number(n) => write!(f, "{n}"),
+            Value::String(s) => write_string(f, s),
+            Value::Array(a) => {
+                write!(f, "[")?;
+                for (i, v) in a.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{v}")?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(o) => {
+                write!(f, "{{")?;
+                for (i, (k, v)) in o.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, k)?;
+                    write!(f, ":{v}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    write!(f, "\"")
+}
+
+/// What went wrong while parsing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedEnd,
+    UnexpectedChar(char),
+    InvalidNumber,
+    InvalidEscape,
+    TrailingCharacters,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {c:?}"),
+            ErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ErrorKind::InvalidEscape => write!(f, "invalid escape sequence"),
+            ErrorKind::TrailingCharacters => write!(f, "trailing characters after value"),
+        }
+    }
+}
+
+/// A parse error, with the position where it occurred
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ErrorKind,
+    /// Byte offset into the input
+    pub offset: usize,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.kind, self.line, self.column
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parse a JSON document
+///
+/// # Examples
+///
+/// ```
+/// use common::json::{parse, ErrorKind, Value};
+///
+/// let doc = parse(r#"{"name": "Aé", "tags": [true, null], "n": -1.5e1}"#).unwrap();
+/// assert_eq!(Some("Aé"), doc.get("name").and_then(Value::as_str));
+/// assert_eq!(Some(-15), doc.get("n").and_then(Value::as_i64));
+/// assert_eq!(r#"{"name":"Aé","tags":[true,null],"n":-15}"#, doc.to_string());
+///
+/// let err = parse("[1,\n 2,\n ]").unwrap_err();
+/// assert_eq!(ErrorKind::UnexpectedChar(']'), err.kind);
+/// assert_eq!((3, 2), (err.line, err.column));
+/// ```
+pub fn parse(input: &str) -> Result<Value, ParseError> {
+    let mut parser = Parser { input, pos: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < input.len() {
+        return Err(parser.error(ErrorKind::TrailingCharacters));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, kind: ErrorKind) -> ParseError {
+        let before = &self.input[..self.pos];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            kind,
+            offset: self.pos,
+            line,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// An error for the character at the current position
+    fn unexpected(&self) -> ParseError {
+        match self.input[self.pos..].chars().next() {
+            Some(c) => self.error(ErrorKind::UnexpectedChar(c)),
+            None => self.error(ErrorKind::UnexpectedEnd),
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), ParseError> {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b't') => self.literal("true", Value::Bool(true)),
+            Some(b'f') => self.literal("false", Value::Bool(false)),
+            Some(b'n') => self.literal("null", Value::Null),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Value) -> Result<Value, ParseError> {
+        for &b in word.as_bytes() {
+            self.expect(b)?;
+        }
+        Ok(value)
+    }
+
+    fn array(&mut self) -> Result<Value, ParseError> {
+        self.expect(b'[')?;
+        let mut items = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Value::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, ParseError> {
+        self.expect(b'{')?;
+        let mut members = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Value::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            members.push((key, self.value()?));
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Value::Object(members));
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.expect(b'"')?;
+        let mut s = String::new();
+
+        loop {
+            // copy everything up to the next quote or escape in one go
+            let rest = &self.input[self.pos..];
+            let end = rest.find(['"', '\\']).unwrap_or(rest.len());
+            s.push_str(&rest[..end]);
+            self.pos += end;
+
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(s);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    s.push(self.escape()?);
+                }
+                _ => return Err(self.error(ErrorKind::UnexpectedEnd)),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, ParseError> {
+        let c = match self.peek() {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.pos += 1;
+                let high = self.hex4()?;
+                if !(0xd800..0xdc00).contains(&high) {
+                    return char::from_u32(high)
+                        .ok_or_else(|| self.error(ErrorKind::InvalidEscape));
+                }
+
+                // a surrogate pair, the low half has to follow as another escape
+                if !self.input[self.pos..].starts_with("\\u") {
+                    return Err(self.error(ErrorKind::InvalidEscape));
+                }
+                self.pos += 2;
+                let low = self.hex4()?;
+                if !(0xdc00..0xe000).contains(&low) {
+                    return Err(self.error(ErrorKind::InvalidEscape));
+                }
+                let c = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+                return char::from_u32(c).ok_or_else(|| self.error(ErrorKind::InvalidEscape));
+            }
+            Some(_) => return Err(self.error(ErrorKind::InvalidEscape)),
+            None => return Err(self.error(ErrorKind::UnexpectedEnd)),
+        };
+        self.pos += 1;
+        Ok(c)
+    }
+
+    fn hex4(&mut self) -> Result<u32, ParseError> {
+        let digits = self
+            .input
+            .get(self.pos..self.pos + 4)
+            .filter(|d| d.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error(ErrorKind::InvalidEscape))?;
+        self.pos += 4;
+        Ok(u32::from_str_radix(digits, 16).unwrap())
+    }
+
+    fn number(&mut self) -> Result<Value, ParseError> {
+        let start = self.pos;
+        let digits = |p: &mut Self| {
+            let from = p.pos;
+            while matches!(p.peek(), Some(b'0'..=b'9')) {
+                p.pos += 1;
+            }
+            p.pos > from
+        };
+
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        let mut valid = digits(self);
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            valid &= digits(self);
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            valid &= digits(self);
+        }
+
+        match self.input[start..self.pos].parse() {
+            Ok(n) if valid => Ok(Value::Number(n)),
+            _ => {
+                self.pos = start;
+                Err(self.error(ErrorKind::InvalidNumber))
+            }
+        }
+    }
+}
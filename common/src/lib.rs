@@ -1,7 +1,32 @@
+pub mod automaton;
+pub mod bitset;
+pub mod combinatorics;
+pub mod cycle;
+pub mod disjoint_set;
+pub mod expr;
 pub mod generate;
+pub mod geometry;
 pub mod grid;
+pub mod hash;
+pub mod hex;
+pub mod interner;
+pub mod interval;
+pub mod json;
+pub mod linalg;
+pub mod math;
+pub mod memo;
+pub mod num;
+pub mod parse;
+pub mod ring;
+pub mod search;
+pub mod sparse_grid;
+pub mod text;
+pub mod vm;
 
+pub use disjoint_set::*;
 pub use grid::*;
+pub use interval::*;
+pub use sparse_grid::*;
 
 pub trait ExpectPrefixExt {
     fn expect_prefix(self, prefix: &str) -> Self;
@@ -0,0 +1,430 @@
+//! Exact linear algebra over the rationals
+//!
+//! Floats lose precision long before puzzle inputs run out of digits, so everything here works
+//! on [`Rational`] numbers backed by a signed integer type (`i128` by default).
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+};
+
+use crate::{math::gcd, num::Signed};
+
+/// A fraction `num / den` in lowest terms, with a positive denominator
+///
+/// # Examples
+///
+/// ```
+/// use common::linalg::Rational;
+///
+/// let a = Rational::new(6, -4);
+/// assert_eq!((-3, 2), (a.num(), a.den()));
+///
+/// let b = a + Rational::from(2);
+/// assert_eq!(Rational::new(1, 2), b);
+/// assert_eq!(Rational::from(3), b * 6);
+/// assert_eq!("-3/2", a.to_string());
+/// assert!(a < b);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational<T = i128> {
+    num: T,
+    den: T,
+}
+
+impl<T: Signed> Rational<T> {
+    /// Build `num / den`, reducing it to lowest terms
+    ///
+    /// # Panics
+    ///
+    /// Panics if `den` is zero.
+    pub fn new(num: T, den: T) -> Self {
+        assert!(den != T::ZERO, "zero denominator");
+        let g = gcd(num, den);
+        let (num, den) = (num / g, den / g);
+        if den < T::ZERO {
+            Self {
+                num: -num,
+                den: -den,
+            }
+        } else {
+            Self { num, den }
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::from(T::ZERO)
+    }
+
+    pub fn one() -> Self {
+        Self::from(T::ONE)
+    }
+
+    pub fn num(self) -> T {
+        self.num
+    }
+
+    pub fn den(self) -> T {
+        self.den
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.num == T::ZERO
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.den == T::ONE
+    }
+
+    /// The value as an integer, if it is one
+    pub fn to_integer(self) -> Option<T> {
+        self.is_integer().then_some(self.num)
+    }
+
+    pub fn abs(self) -> Self {
+        if self.num < T::ZERO {
+            -self
+        } else {
+            self
+        }
+    }
+
+    /// The reciprocal `den / num`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is zero.
+    pub fn recip(self) -> Self {
+        Self::new(self.den, self.num)
+    }
+}
+
+impl<T: Signed> Default for Rational<T> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<T: Signed> From<T> for Rational<T> {
+    fn from(n: T) -> Self {
+        Self {
+            num: n,
+            den: T::ONE,
+        }
+    }
+}
+
+impl<T: Signed> Add for Rational<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        // go through the lcm of the denominators to keep intermediate values small
+        let g = gcd(self.den, rhs.den);
+        let (l, r) = (rhs.den / g, self.den / g);
+        Self::new(self.num * l + rhs.num * r, self.den * l)
+    }
+}
+
+impl<T: Signed> Sub for Rational<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        let g = gcd(self.den, rhs.den);
+        let (l, r) = (rhs.den / g, self.den / g);
+        Self::new(self.num * l - rhs.num * r, self.den * l)
+    }
+}
+
+impl<T: Signed> Mul for Rational<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        // cancel crosswise first, so the products are already in lowest terms
+        let g1 = gcd(self.num, rhs.den);
+        let g2 = gcd(rhs.num, self.den);
+        Self {
+            num: (self.num / g1) * (rhs.num / g2),
+            den: (self.den / g2) * (rhs.den / g1),
+        }
+    }
+}
+
+impl<T: Signed> Div for Rational<T> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        Mul::mul(self, rhs.recip())
+    }
+}
+
+impl<T: Signed> Neg for Rational<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl<T: Signed> AddAssign for Rational<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Signed> SubAssign for Rational<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+macro_rules! impl_integer_ops {
+    ($($trait:ident $fn:ident),*) => {$(
+        impl<T: Signed> $trait<T> for Rational<T> {
+            type Output = Self;
+
+            fn $fn(self, rhs: T) -> Self {
+                self.$fn(Self::from(rhs))
+            }
+        }
+    )*};
+}
+
+impl_integer_ops!(Add add, Sub sub, Mul mul, Div div);
+
+impl<T: Signed> PartialOrd for Rational<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Signed> Ord for Rational<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // denominators are positive, so cross multiplying keeps the order
+        (self.num * other.den).cmp(&(other.num * self.den))
+    }
+}
+
+impl<T: Signed> fmt::Display for Rational<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == T::ONE {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+/// The outcome of solving a system of linear equations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution<T = i128> {
+    /// Exactly one solution
+    Unique(Vec<Rational<T>>),
+    /// Infinitely many solutions; `free` is the number of free variables
+    Infinite { free: usize },
+    /// The equations contradict each other
+    Inconsistent,
+}
+
+impl<T: Signed> Solution<T> {
+    /// The solution, if there is exactly one
+    pub fn unique(self) -> Option<Vec<Rational<T>>> {
+        match self {
+            Solution::Unique(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// The solution, if there is exactly one and all of its values are integers
+    pub fn integers(self) -> Option<Vec<T>> {
+        self.unique()?
+            .into_iter()
+            .map(Rational::to_integer)
+            .collect()
+    }
+}
+
+/// Solve `a · x = b`
+///
+/// `a` has one row per equation and one column per unknown; it does not need to be square.
+/// 2x2 and 3x3 systems with a unique solution are solved by Cramer's rule, everything else by
+/// Gauss-Jordan elimination.
+///
+/// # Panics
+///
+/// Panics if the rows of `a` differ in length, or `b` does not have one entry per row.
+///
+/// # Examples
+///
+/// ```
+/// use common::linalg::{solve, Rational, Solution};
+///
+/// // x + y + z = 6, 2y + 5z = -4, 2x + 5y - z = 27
+/// let a = [[1, 1, 1], [0, 2, 5], [2, 5, -1]].map(|r| r.map(Rational::from).to_vec());
+/// let b = [6, -4, 27].map(Rational::from);
+/// assert_eq!(Some(vec![5, 3, -2]), solve(&a, &b).integers());
+///
+/// // x + y = 1, 2x + 2y = 3
+/// let a = [[1, 1], [2, 2]].map(|r| r.map(Rational::from).to_vec());
+/// let b = [1, 3].map(Rational::from);
+/// assert_eq!(Solution::Inconsistent, solve(&a, &b));
+///
+/// // x + y = 1, 2x + 2y = 2
+/// let b = [1, 2].map(Rational::from);
+/// assert_eq!(Solution::Infinite { free: 1 }, solve(&a, &b));
+/// ```
+pub fn solve<T: Signed>(a: &[Vec<Rational<T>>], b: &[Rational<T>]) -> Solution<T> {
+    assert_eq!(a.len(), b.len(), "need one right hand side per equation");
+    let n = a.first().map_or(0, Vec::len);
+    assert!(a.iter().all(|row| row.len() == n), "ragged matrix");
+
+    let fast = match (a.len(), n) {
+        (2, 2) => cramer2([[a[0][0], a[0][1]], [a[1][0], a[1][1]]], [b[0], b[1]]).map(Vec::from),
+        (3, 3) => cramer3(
+            [0, 1, 2].map(|i| [a[i][0], a[i][1], a[i][2]]),
+            [b[0], b[1], b[2]],
+        )
+        .map(Vec::from),
+        _ => None,
+    };
+
+    match fast {
+        Some(x) => Solution::Unique(x),
+        None => gauss_jordan(a, b),
+    }
+}
+
+/// Solve `a · x = b` for an integer matrix; see [`solve`]
+///
+/// # Examples
+///
+/// ```
+/// use common::linalg::{solve_integer, Rational};
+///
+/// let x = solve_integer(&[vec![2, 1], vec![1, 3]], &[3, 5]).unique().unwrap();
+/// assert_eq!(vec![Rational::new(4, 5), Rational::new(7, 5)], x);
+/// ```
+pub fn solve_integer<T: Signed>(a: &[Vec<T>], b: &[T]) -> Solution<T> {
+    let a: Vec<Vec<_>> = a
+        .iter()
+        .map(|row| row.iter().copied().map(Rational::from).collect())
+        .collect();
+    let b: Vec<_> = b.iter().copied().map(Rational::from).collect();
+    solve(&a, &b)
+}
+
+/// Solve a 2x2 system by Cramer's rule; `None` if it has no unique solution
+///
+/// # Examples
+///
+/// ```
+/// use common::linalg::{cramer2, Rational};
+///
+/// // 94a + 22b = 8400, 34a + 67b = 5400
+/// let a = [[94, 22], [34, 67]].map(|r| r.map(Rational::<i64>::from));
+/// let b = [8400, 5400].map(Rational::from);
+/// assert_eq!(Some([80, 40].map(Rational::from)), cramer2(a, b));
+///
+/// let parallel = [[1, 2], [2, 4]].map(|r| r.map(Rational::<i64>::from));
+/// assert_eq!(None, cramer2(parallel, b));
+/// ```
+pub fn cramer2<T: Signed>(
+    a: [[Rational<T>; 2]; 2],
+    b: [Rational<T>; 2],
+) -> Option<[Rational<T>; 2]> {
+    let det = det2(a);
+    if det.is_zero() {
+        return None;
+    }
+
+    Some([0, 1].map(|col| {
+        let mut m = a;
+        for (row, &v) in m.iter_mut().zip(&b) {
+            row[col] = v;
+        }
+        det2(m) / det
+    }))
+}
+
+/// Solve a 3x3 system by Cramer's rule; `None` if it has no unique solution
+pub fn cramer3<T: Signed>(
+    a: [[Rational<T>; 3]; 3],
+    b: [Rational<T>; 3],
+) -> Option<[Rational<T>; 3]> {
+    let det = det3(a);
+    if det.is_zero() {
+        return None;
+    }
+
+    Some([0, 1, 2].map(|col| {
+        let mut m = a;
+        for (row, &v) in m.iter_mut().zip(&b) {
+            row[col] = v;
+        }
+        det3(m) / det
+    }))
+}
+
+fn det2<T: Signed>(m: [[Rational<T>; 2]; 2]) -> Rational<T> {
+    m[0][0] * m[1][1] - m[0][1] * m[1][0]
+}
+
+fn det3<T: Signed>(m: [[Rational<T>; 3]; 3]) -> Rational<T> {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+fn gauss_jordan<T: Signed>(a: &[Vec<Rational<T>>], b: &[Rational<T>]) -> Solution<T> {
+    let n = a.first().map_or(0, Vec::len);
+
+    // augmented matrix [a | b]
+    let mut m: Vec<Vec<_>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, &rhs)| row.iter().copied().chain([rhs]).collect())
+        .collect();
+
+    // bring into reduced row echelon form, remembering which column each pivot row belongs to
+    let mut pivots = Vec::new();
+    for col in 0..n {
+        let r = pivots.len();
+        let Some(p) = (r..m.len()).find(|&i| !m[i][col].is_zero()) else {
+            continue;
+        };
+        m.swap(r, p);
+
+        let inv = m[r][col].recip();
+        for v in &mut m[r] {
+            *v = *v * inv;
+        }
+
+        let pivot_row = m[r].clone();
+        for (i, row) in m.iter_mut().enumerate() {
+            let factor = row[col];
+            if i == r || factor.is_zero() {
+                continue;
+            }
+            for (v, &p) in row.iter_mut().zip(&pivot_row).skip(col) {
+                *v -= factor * p;
+            }
+        }
+
+        pivots.push(col);
+    }
+
+    // a leftover row reading 0 = c with c != 0
+    if m[pivots.len()..].iter().any(|row| !row[n].is_zero()) {
+        return Solution::Inconsistent;
+    }
+    if pivots.len() < n {
+        return Solution::Infinite {
+            free: n - pivots.len(),
+        };
+    }
+
+    Solution::Unique(m.iter().take(n).map(|row| row[n]).collect())
+}
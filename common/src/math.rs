@@ -0,0 +1,315 @@
+//! Number theory helpers
+//!
+//! Everything is generic over the primitive integer types. Modular arithmetic never overflows
+//! as long as the modulus itself fits into the type.
+
+use crate::num::{Integer, Signed};
+
+/// Greatest common divisor; always non-negative
+///
+/// # Examples
+///
+/// ```
+/// use common::math::gcd;
+///
+/// assert_eq!(6, gcd(48u32, 18));
+/// assert_eq!(6, gcd(-48i64, 18));
+/// assert_eq!(5, gcd(0, 5));
+/// ```
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    abs(a)
+}
+
+/// Least common multiple; always non-negative, `None` if it does not fit into `T`
+///
+/// # Examples
+///
+/// ```
+/// use common::math::lcm;
+///
+/// assert_eq!(Some(36), lcm(12u64, 18));
+/// assert_eq!(Some(0), lcm(0, 7));
+/// assert_eq!(None, lcm(200u8, 3));
+/// ```
+pub fn lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    let l = (a / gcd(a, b)).checked_mul(b)?;
+    // the only value whose absolute value does not fit
+    (l != T::MIN).then(|| abs(l))
+}
+
+/// Greatest common divisor of all numbers; `0` if there are none
+///
+/// # Examples
+///
+/// ```
+/// use common::math::gcd_all;
+///
+/// assert_eq!(4, gcd_all([8, 12, 20]));
+/// ```
+pub fn gcd_all<T: Integer>(nums: impl IntoIterator<Item = T>) -> T {
+    nums.into_iter().fold(T::ZERO, gcd)
+}
+
+/// Least common multiple of all numbers; `1` if there are none, `None` if it does not fit
+/// into `T`
+///
+/// # Examples
+///
+/// ```
+/// use common::math::lcm_all;
+///
+/// assert_eq!(Some(6_u64), lcm_all([2, 3, 6]));
+/// assert_eq!(Some(96577), lcm_all([23, 19, 13, 17]));
+/// assert_eq!(None, lcm_all([7u8, 11, 13]));
+/// ```
+pub fn lcm_all<T: Integer>(nums: impl IntoIterator<Item = T>) -> Option<T> {
+    nums.into_iter().try_fold(T::ONE, lcm)
+}
+
+/// Extended euclidean algorithm
+///
+/// Returns `(g, x, y)` so that `a * x + b * y == g == gcd(a, b)`.
+///
+/// # Examples
+///
+/// ```
+/// use common::math::extended_gcd;
+///
+/// let (g, x, y) = extended_gcd(240, 46);
+/// assert_eq!(2, g);
+/// assert_eq!(g, 240 * x + 46 * y);
+/// ```
+pub fn extended_gcd<T: Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (T::ONE, T::ZERO);
+    let (mut old_t, mut t) = (T::ZERO, T::ONE);
+
+    while r != T::ZERO {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+
+    if old_r < T::ZERO {
+        (-old_r, -old_s, -old_t)
+    } else {
+        (old_r, old_s, old_t)
+    }
+}
+
+/// `a mod m`, in the range `0..m`
+///
+/// # Examples
+///
+/// ```
+/// use common::math::rem_euclid;
+///
+/// assert_eq!(2, rem_euclid(-5, 7));
+/// ```
+pub fn rem_euclid<T: Integer>(a: T, m: T) -> T {
+    let r = a % m;
+    if r < T::ZERO {
+        r + m
+    } else {
+        r
+    }
+}
+
+/// `(a + b) mod m` without overflowing; `a` and `b` must already be reduced
+fn add_mod<T: Integer>(a: T, b: T, m: T) -> T {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+/// `(a * b) mod m` without overflowing
+///
+/// # Examples
+///
+/// ```
+/// use common::math::mul_mod;
+///
+/// assert_eq!(2, mul_mod(u64::MAX - 1, u64::MAX - 2, u64::MAX));
+/// ```
+pub fn mul_mod<T: Integer>(a: T, b: T, m: T) -> T {
+    let (mut a, mut b) = (rem_euclid(a, m), rem_euclid(b, m));
+    if let Some(p) = a.checked_mul(b) {
+        return p % m;
+    }
+
+    // double-and-add, so intermediate values never exceed `m`
+    let two = T::ONE + T::ONE;
+    let mut result = T::ZERO;
+    while b > T::ZERO {
+        if b % two == T::ONE {
+            result = add_mod(result, a, m);
+        }
+        a = add_mod(a, a, m);
+        b = b / two;
+    }
+    result
+}
+
+/// `base ^ exp mod m`
+///
+/// # Examples
+///
+/// ```
+/// use common::math::mod_pow;
+///
+/// assert_eq!(445, mod_pow(4u32, 13, 497));
+/// assert_eq!(1, mod_pow(-1i64, 2, 7));
+/// assert_eq!(0, mod_pow(5u8, 0, 1));
+/// ```
+pub fn mod_pow<T: Integer>(base: T, exp: T, m: T) -> T {
+    let two = T::ONE + T::ONE;
+    let mut base = rem_euclid(base, m);
+    let mut exp = exp;
+    let mut result = T::ONE % m;
+
+    while exp > T::ZERO {
+        if exp % two == T::ONE {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp = exp / two;
+    }
+    result
+}
+
+/// Multiplicative inverse of `a` modulo `m`, if it exists
+///
+/// # Examples
+///
+/// ```
+/// use common::math::mod_inv;
+///
+/// assert_eq!(Some(4), mod_inv(3, 11));
+/// assert_eq!(None, mod_inv(4, 8));
+/// ```
+pub fn mod_inv<T: Signed>(a: T, m: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(rem_euclid(a, m), m);
+    (g == T::ONE).then(|| rem_euclid(x, m))
+}
+
+/// Solve a system of congruences `x = a_i (mod m_i)` with the Chinese Remainder Theorem
+///
+/// The moduli do not need to be pairwise coprime. Returns `(x, lcm(m_i))` with the smallest
+/// non-negative `x`, or `None` if the congruences contradict each other or the combined modulus
+/// does not fit into `T`.
+///
+/// # Examples
+///
+/// ```
+/// use common::math::crt;
+///
+/// assert_eq!(Some((23, 105)), crt([(2, 3), (3, 5), (2, 7)]));
+/// assert_eq!(Some((10, 12)), crt([(4, 6), (2, 4)]));
+/// assert_eq!(None, crt([(1, 4), (2, 6)]));
+/// assert_eq!(None, crt([(1, 97i8), (2, 89)]));
+/// ```
+pub fn crt<T: Signed>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    congruences
+        .into_iter()
+        .try_fold((T::ZERO, T::ONE), |(a1, m1), (a2, m2)| {
+            let a2 = rem_euclid(a2, m2);
+            let g = gcd(m1, m2);
+            let diff = a2 - a1;
+            if diff % g != T::ZERO {
+                return None;
+            }
+
+            // solve m1 * k = diff (mod m2) for k
+            let m2g = m2 / g;
+            let k = mul_mod(diff / g, mod_inv(m1 / g, m2g)?, m2g);
+
+            let lcm = (m1 / g).checked_mul(m2)?;
+            Some((add_mod(a1, mul_mod(m1, k, lcm), lcm), lcm))
+        })
+}
+
+/// Sum of all divisors `σ(n)` for every `n` in `0..=limit`
+///
+/// # Examples
+///
+/// ```
+/// use common::math::divisor_sums;
+///
+/// assert_eq!(vec![0, 1, 3, 4, 7, 6, 12, 8, 15, 13], divisor_sums(9));
+/// ```
+pub fn divisor_sums(limit: usize) -> Vec<usize> {
+    divisor_sums_bounded(limit, usize::MAX)
+}
+
+/// Like [`divisor_sums`], but each divisor `d` only counts for its first `max_multiple`
+/// multiples
+///
+/// # Examples
+///
+/// ```
+/// use common::math::divisor_sums_bounded;
+///
+/// // 1 only divides 1, 2; 2 only divides 2, 4; ...
+/// assert_eq!(vec![0, 1, 3, 3, 6, 5, 9, 7, 12], divisor_sums_bounded(8, 2));
+/// ```
+pub fn divisor_sums_bounded(limit: usize, max_multiple: usize) -> Vec<usize> {
+    let mut sums = vec![0; limit + 1];
+    for d in 1..=limit {
+        for n in (d..=limit).step_by(d).take(max_multiple) {
+            sums[n] += d;
+        }
+    }
+    sums
+}
+
+/// Integer square root, rounded down
+///
+/// # Panics
+///
+/// Panics if `n` is negative.
+///
+/// # Examples
+///
+/// ```
+/// use common::math::isqrt;
+///
+/// assert_eq!(4, isqrt(24u32));
+/// assert_eq!(5, isqrt(25u32));
+/// assert_eq!(u32::MAX as u64, isqrt(u64::MAX));
+/// ```
+pub fn isqrt<T: Integer>(n: T) -> T {
+    assert!(n >= T::ZERO, "square root of negative number {n}");
+
+    let two = T::ONE + T::ONE;
+    if n < two {
+        return n;
+    }
+
+    // Newton's method, starting above the root so it converges from above
+    let mut x = n / two + T::ONE;
+    loop {
+        let y = (x + n / x) / two;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+fn abs<T: Integer>(a: T) -> T {
+    if a < T::ZERO {
+        T::ZERO - a
+    } else {
+        a
+    }
+}
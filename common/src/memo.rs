@@ -0,0 +1,135 @@
+//! Caching the results of recursive functions
+
+use std::{collections::HashMap, hash::Hash};
+
+/// A cache for the results of a function, with hit and miss counters
+///
+/// The computation is passed to [`Memo::get_or_compute`] together with the key. It gets the memo
+/// itself back, so a recursive function can simply take the memo as an argument and use it for
+/// its recursive calls.
+///
+/// # Examples
+///
+/// ```
+/// use common::memo::Memo;
+///
+/// fn fib(memo: &mut Memo<u32, u64>, n: u32) -> u64 {
+///     memo.get_or_compute(n, |memo| match n {
+///         0 | 1 => n.into(),
+///         _ => fib(memo, n - 1) + fib(memo, n - 2),
+///     })
+/// }
+///
+/// let mut memo = Memo::new();
+/// assert_eq!(12586269025, fib(&mut memo, 50));
+/// assert_eq!((48, 51), (memo.hits(), memo.misses()));
+/// assert_eq!(51, memo.len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    capacity: Option<usize>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+            capacity: None,
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A memo holding at most `capacity` results
+    ///
+    /// Once it is full, the whole cache is dropped before the next result is stored. That keeps
+    /// memory bounded for searches whose subproblems are mostly used shortly after they are
+    /// solved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::memo::Memo;
+    ///
+    /// let mut memo = Memo::bounded(2);
+    /// for n in [1, 2, 3, 3] {
+    ///     memo.get_or_compute(n, |_| n * n);
+    /// }
+    /// assert_eq!(1, memo.len());
+    /// assert_eq!((1, 3), (memo.hits(), memo.misses()));
+    /// ```
+    pub fn bounded(capacity: usize) -> Self {
+        assert!(capacity > 0, "a memo needs room for at least one result");
+        Self {
+            capacity: Some(capacity),
+            ..Self::default()
+        }
+    }
+
+    /// The cached result for `key`, or the result of `compute`, which is then cached
+    ///
+    /// `compute` receives the memo, to use for recursive calls.
+    pub fn get_or_compute<F>(&mut self, key: K, compute: F) -> V
+    where
+        F: FnOnce(&mut Self) -> V,
+    {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+
+        self.misses += 1;
+        let value = compute(self);
+        if self.capacity.is_some_and(|c| self.cache.len() >= c) {
+            self.cache.clear();
+        }
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// The cached result for `key`, without touching the counters
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// Number of cached results
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Number of lookups answered from the cache
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Number of lookups that had to compute their result
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Fraction of lookups answered from the cache, 0 if there were none
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+
+    /// Drop all cached results, keeping the counters
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
@@ -0,0 +1,248 @@
+//! Reusable [`nom`] parsers for the shapes puzzle inputs usually come in
+//!
+//! All parsers work on `&str` with nom's default error type, so they combine freely with nom's
+//! own combinators. Run the parser for a whole input with [`finish_all`].
+//!
+//! # Examples
+//!
+//! ```
+//! use common::parse::{blocks, field, finish_all, lines, signed, xy};
+//! use nom::{bytes::complete::tag, sequence::separated_pair};
+//!
+//! let input = "Sensor: x=2, y=18\nBeacon: x=-2, y=15\n\nSensor: x=9, y=16\nBeacon: x=10, y=16\n";
+//! let pair = separated_pair(field("Sensor", xy::<i32>), tag("\n"), field("Beacon", xy::<i32>));
+//!
+//! let pairs = finish_all(blocks(pair), input).unwrap();
+//! assert_eq!(vec![((2, 18), (-2, 15)), ((9, 16), (10, 16))], pairs);
+//!
+//! let err = finish_all(lines(signed::<i32>), "1\n-2\nthree\n").unwrap_err();
+//! assert_eq!((3, 1), (err.line, err.column));
+//! ```
+
+use std::{error::Error, fmt, str::FromStr};
+
+use nom::{
+    bytes::complete::{tag, take_while1},
+    character::complete::{char, digit1, line_ending, one_of, space0, space1},
+    combinator::{map, map_res, opt, recognize},
+    multi::separated_list1,
+    sequence::{pair, preceded, separated_pair, tuple},
+    Parser,
+};
+
+pub use nom::IResult;
+
+use crate::Grid;
+
+type NomError<'a> = nom::error::Error<&'a str>;
+
+/// An unsigned decimal integer
+///
+/// # Examples
+///
+/// ```
+/// use common::parse::unsigned;
+///
+/// assert_eq!(Ok((" apples", 42u8)), unsigned::<u8>("42 apples"));
+/// assert!(unsigned::<u8>("-1").is_err());
+/// assert!(unsigned::<u8>("256").is_err());
+/// ```
+pub fn unsigned<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// A decimal integer with an optional sign
+///
+/// # Examples
+///
+/// ```
+/// use common::parse::signed;
+///
+/// assert_eq!(Ok(("", -7i64)), signed::<i64>("-7"));
+/// assert_eq!(Ok(("", 7i64)), signed::<i64>("+7"));
+/// ```
+pub fn signed<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(input)
+}
+
+/// `name=value`
+pub fn labeled<'a, O, F>(name: &'static str, value: F) -> impl FnMut(&'a str) -> IResult<&'a str, O>
+where
+    F: Parser<&'a str, O, NomError<'a>>,
+{
+    preceded(pair(tag(name), char('=')), value)
+}
+
+/// A coordinate pair written as `x=1, y=2`
+///
+/// # Examples
+///
+/// ```
+/// use common::parse::xy;
+///
+/// assert_eq!(Ok((": beacon", (2, -18))), xy::<i32>("x=2, y=-18: beacon"));
+/// ```
+pub fn xy<T: FromStr>(input: &str) -> IResult<&str, (T, T)> {
+    separated_pair(
+        labeled("x", signed),
+        pair(char(','), space0),
+        labeled("y", signed),
+    )(input)
+}
+
+/// A non-empty list of `item`s separated by commas, with optional spaces after them
+///
+/// # Examples
+///
+/// ```
+/// use common::parse::{comma_list, unsigned};
+///
+/// assert_eq!(Ok(("", vec![79, 98])), comma_list(unsigned::<u32>)("79, 98"));
+/// assert_eq!(Ok(("", vec![3, 4, 3])), comma_list(unsigned::<u32>)("3,4,3"));
+/// ```
+pub fn comma_list<'a, O, F>(item: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, NomError<'a>>,
+{
+    separated_list1(pair(char(','), space0), item)
+}
+
+/// A non-empty list of `item`s separated by spaces or tabs
+///
+/// # Examples
+///
+/// ```
+/// use common::parse::{signed, space_list};
+///
+/// assert_eq!(Ok(("\n", vec![0, 3, -6])), space_list(signed::<i32>)("0  3 -6\n"));
+/// ```
+pub fn space_list<'a, O, F>(item: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, NomError<'a>>,
+{
+    separated_list1(space1, item)
+}
+
+/// A non-empty list of `item`s, one per line
+///
+/// The final line ending is left in the input, so blocks of lines can be separated by blank lines.
+pub fn lines<'a, O, F>(item: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, NomError<'a>>,
+{
+    separated_list1(line_ending, item)
+}
+
+/// A non-empty list of `item`s separated by blank lines
+pub fn blocks<'a, O, F>(item: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, NomError<'a>>,
+{
+    separated_list1(pair(line_ending, line_ending), item)
+}
+
+/// The value of a `key: value` line, which may be indented
+///
+/// # Examples
+///
+/// ```
+/// use common::parse::{comma_list, field, unsigned};
+///
+/// let mut items = field("Starting items", comma_list(unsigned::<u32>));
+/// assert_eq!(Ok(("", vec![54, 65])), items("  Starting items: 54, 65"));
+/// ```
+pub fn field<'a, O, F>(key: &'static str, value: F) -> impl FnMut(&'a str) -> IResult<&'a str, O>
+where
+    F: Parser<&'a str, O, NomError<'a>>,
+{
+    preceded(tuple((space0, tag(key), char(':'), space0)), value)
+}
+
+/// A block of non-empty lines, converted into a grid cell by cell with `cell`
+///
+/// # Examples
+///
+/// ```
+/// use common::parse::{blocks, grid};
+///
+/// let (_, grids) = blocks(grid(|c| c == '#'))("#.\n.#\n\n##\n").unwrap();
+/// assert_eq!(2, grids.len());
+/// assert_eq!(vec![true, true], grids[1].cells());
+/// ```
+pub fn grid<'a, T, F>(cell: F) -> impl FnMut(&'a str) -> IResult<&'a str, Grid<T>>
+where
+    F: Fn(char) -> T,
+{
+    map(
+        recognize(separated_list1(
+            line_ending,
+            take_while1(|c| c != '\n' && c != '\r'),
+        )),
+        move |block| Grid::parse(block, &cell),
+    )
+}
+
+/// Where and why parsing an input failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// Byte offset into the input
+    pub offset: usize,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+}
+
+impl ParseError {
+    fn new(input: &str, rest: &str, message: String) -> Self {
+        let offset = input.len() - rest.len();
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            message,
+            offset,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// Run `parser` on the whole of `input`
+///
+/// Trailing whitespace is allowed, anything else left over is an error.
+pub fn finish_all<'a, O, F>(mut parser: F, input: &'a str) -> Result<O, ParseError>
+where
+    F: Parser<&'a str, O, NomError<'a>>,
+{
+    match parser.parse(input) {
+        Ok((rest, value)) if rest.trim().is_empty() => Ok(value),
+        Ok((rest, _)) => Err(ParseError::new(
+            input,
+            rest.trim_start(),
+            "unexpected trailing input".to_string(),
+        )),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(ParseError::new(
+            input,
+            e.input,
+            format!("expected {}", e.code.description()),
+        )),
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::new(
+            input,
+            "",
+            "unexpected end of input".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,311 @@
+//! A circular doubly linked list, for puzzles that keep moving elements around a circle
+
+use std::iter::FusedIterator;
+
+/// Handle of an element in a [`Ring`]
+///
+/// Handles are assigned in insertion order, starting at 0, and stay valid until the element is
+/// removed. They are not reused.
+pub type NodeId = usize;
+
+/// A circular sequence with constant time insertion and removal, and a cursor
+///
+/// The links are kept in arrays indexed by [`NodeId`], so an element can be found again in
+/// constant time no matter how often it has moved.
+///
+/// # Examples
+///
+/// ```
+/// use common::ring::Ring;
+///
+/// let mut ring: Ring<_> = (1..=5).collect();
+/// assert_eq!(Some(&1), ring.current());
+///
+/// ring.rotate(-2);
+/// assert_eq!(Some(&4), ring.current());
+/// ring.insert_after(10);
+/// assert_eq!(vec![4, 10, 5, 1, 2, 3], ring.iter().copied().collect::<Vec<_>>());
+///
+/// assert_eq!(Some(4), ring.remove());
+/// assert_eq!(Some(&10), ring.current());
+///
+/// // handles stay valid while elements move
+/// let two = 1;
+/// ring.move_by(two, 2);
+/// assert_eq!(vec![10, 2, 5, 1, 3], ring.iter().copied().collect::<Vec<_>>());
+/// assert_eq!(Some(&3), ring.get(ring.offset(two, 3)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ring<T> {
+    values: Vec<Option<T>>,
+    next: Vec<NodeId>,
+    prev: Vec<NodeId>,
+    cursor: Option<NodeId>,
+    len: usize,
+}
+
+impl<T> Default for Ring<T> {
+    fn default() -> Self {
+        Self {
+            values: Vec::new(),
+            next: Vec::new(),
+            prev: Vec::new(),
+            cursor: None,
+            len: 0,
+        }
+    }
+}
+
+impl<T> Ring<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An empty ring with room for `capacity` insertions
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: Vec::with_capacity(capacity),
+            next: Vec::with_capacity(capacity),
+            prev: Vec::with_capacity(capacity),
+            cursor: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The element under the cursor
+    pub fn cursor(&self) -> Option<NodeId> {
+        self.cursor
+    }
+
+    /// Move the cursor onto the element `id`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element has been removed.
+    pub fn seek(&mut self, id: NodeId) {
+        assert!(self.contains(id), "no element {id} in the ring");
+        self.cursor = Some(id);
+    }
+
+    /// The value under the cursor
+    pub fn current(&self) -> Option<&T> {
+        self.get(self.cursor?)
+    }
+
+    pub fn current_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.cursor?)
+    }
+
+    /// Whether `id` is an element of the ring
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.values.get(id).is_some_and(Option::is_some)
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.values.get(id)?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        self.values.get_mut(id)?.as_mut()
+    }
+
+    /// The element after `id`
+    pub fn next(&self, id: NodeId) -> NodeId {
+        self.next[id]
+    }
+
+    /// The element before `id`
+    pub fn prev(&self, id: NodeId) -> NodeId {
+        self.prev[id]
+    }
+
+    /// The element `k` places after `id`, or before it if `k` is negative
+    ///
+    /// Walks at most half way around the ring.
+    pub fn offset(&self, mut id: NodeId, k: isize) -> NodeId {
+        if self.len == 0 {
+            return id;
+        }
+        let forward = k.rem_euclid(self.len as isize) as usize;
+        if forward <= self.len / 2 {
+            for _ in 0..forward {
+                id = self.next[id];
+            }
+        } else {
+            for _ in forward..self.len {
+                id = self.prev[id];
+            }
+        }
+        id
+    }
+
+    /// Move the cursor `k` places forward, or backward if `k` is negative
+    pub fn rotate(&mut self, k: isize) {
+        if let Some(cursor) = self.cursor {
+            self.cursor = Some(self.offset(cursor, k));
+        }
+    }
+
+    /// Insert `value` after the cursor, returning its handle
+    ///
+    /// The cursor stays where it is, unless the ring was empty; then it moves onto the new
+    /// element.
+    pub fn insert_after(&mut self, value: T) -> NodeId {
+        let id = self.values.len();
+        self.values.push(Some(value));
+        self.next.push(id);
+        self.prev.push(id);
+        match self.cursor {
+            Some(cursor) => self.link_after(cursor, id),
+            None => self.cursor = Some(id),
+        }
+        self.len += 1;
+        id
+    }
+
+    /// Insert `value` before the cursor, i.e. at the end when iterating from the cursor
+    pub fn push_back(&mut self, value: T) -> NodeId {
+        match self.cursor {
+            Some(cursor) => {
+                self.cursor = Some(self.prev[cursor]);
+                let id = self.insert_after(value);
+                self.cursor = Some(cursor);
+                id
+            }
+            None => self.insert_after(value),
+        }
+    }
+
+    /// Remove the element under the cursor, moving the cursor onto the next one
+    pub fn remove(&mut self) -> Option<T> {
+        let id = self.cursor?;
+        let next = self.next[id];
+        self.unlink(id);
+        self.len -= 1;
+        self.cursor = (self.len > 0).then_some(next);
+        self.values[id].take()
+    }
+
+    /// Move the element `id` by `k` places, forward if `k` is positive
+    ///
+    /// The other elements keep their order; moving by `len - 1` places is a full turn. The cursor
+    /// stays on the same element.
+    pub fn move_by(&mut self, id: NodeId, k: isize) {
+        if self.len <= 1 {
+            return;
+        }
+        let before = self.prev[id];
+        self.unlink(id);
+        self.len -= 1;
+        let target = self.offset(before, k);
+        self.link_after(target, id);
+        self.len += 1;
+    }
+
+    /// The values, starting at the cursor
+    pub fn iter(&self) -> Iter<'_, T> {
+        match self.cursor {
+            Some(cursor) => self.iter_from(cursor),
+            None => Iter {
+                ring: self,
+                id: 0,
+                remaining: 0,
+            },
+        }
+    }
+
+    /// The values, starting at `id`
+    pub fn iter_from(&self, id: NodeId) -> Iter<'_, T> {
+        Iter {
+            ring: self,
+            id,
+            remaining: self.len,
+        }
+    }
+
+    /// The handle of the first element, starting at the cursor, whose value satisfies `pred`
+    pub fn find(&self, mut pred: impl FnMut(&T) -> bool) -> Option<NodeId> {
+        let mut id = self.cursor?;
+        for _ in 0..self.len {
+            if self.get(id).is_some_and(&mut pred) {
+                return Some(id);
+            }
+            id = self.next[id];
+        }
+        None
+    }
+
+    fn link_after(&mut self, at: NodeId, id: NodeId) {
+        let next = self.next[at];
+        self.next[at] = id;
+        self.prev[id] = at;
+        self.next[id] = next;
+        self.prev[next] = id;
+    }
+
+    fn unlink(&mut self, id: NodeId) {
+        let (prev, next) = (self.prev[id], self.next[id]);
+        self.next[prev] = next;
+        self.prev[next] = prev;
+        self.next[id] = id;
+        self.prev[id] = id;
+    }
+}
+
+impl<T> FromIterator<T> for Ring<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut ring = Self::with_capacity(iter.size_hint().0);
+        for value in iter {
+            ring.push_back(value);
+        }
+        ring
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Ring<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the values of a [`Ring`], once around
+#[derive(Debug, Clone)]
+pub struct Iter<'a, T> {
+    ring: &'a Ring<T>,
+    id: NodeId,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let value = self.ring.get(self.id);
+        self.id = self.ring.next[self.id];
+        value
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
@@ -0,0 +1,429 @@
+//! Generic graph search
+//!
+//! All searches are generic over the node type `N` and take a `successors` closure that
+//! returns the neighbours of a node (with their edge cost for weighted searches). Every
+//! search accepts multiple start nodes.
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Result of a search: distances and predecessors of all reached nodes
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+    dist: HashMap<N, C>,
+    prev: HashMap<N, N>,
+    goal: Option<N>,
+}
+
+impl<N, C> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy,
+{
+    fn new() -> Self {
+        Self {
+            dist: HashMap::new(),
+            prev: HashMap::new(),
+            goal: None,
+        }
+    }
+
+    /// Distance from the closest start node to `node`, if it was reached
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.dist.get(node).copied()
+    }
+
+    /// Distances of all reached nodes
+    pub fn distances(&self) -> &HashMap<N, C> {
+        &self.dist
+    }
+
+    /// The node `node` was reached from. Start nodes have no predecessor.
+    pub fn predecessor(&self, node: &N) -> Option<&N> {
+        self.prev.get(node)
+    }
+
+    /// Full path from a start node to `node`, both inclusive
+    pub fn path(&self, node: &N) -> Option<Vec<N>> {
+        if !self.dist.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        let mut current = node;
+        while let Some(prev) = self.prev.get(current) {
+            path.push(prev.clone());
+            current = prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The goal node the search stopped at, if any
+    pub fn goal(&self) -> Option<&N> {
+        self.goal.as_ref()
+    }
+
+    /// Distance to the goal node, if one was found
+    pub fn goal_distance(&self) -> Option<C> {
+        self.goal.as_ref().and_then(|g| self.distance(g))
+    }
+
+    /// Path to the goal node, if one was found
+    pub fn goal_path(&self) -> Option<Vec<N>> {
+        self.goal.as_ref().and_then(|g| self.path(g))
+    }
+
+    /// All nodes at exactly distance `k`, in arbitrary order
+    pub fn at_distance(&self, k: C) -> impl Iterator<Item = &N>
+    where
+        C: PartialEq,
+    {
+        self.dist
+            .iter()
+            .filter_map(move |(n, &d)| (d == k).then_some(n))
+    }
+}
+
+/// Breadth-first search over the whole reachable graph
+///
+/// # Examples
+///
+/// ```
+/// use common::search::bfs;
+///
+/// // walk a number line, one step left or right, within 0..=10
+/// let succ = |&n: &i32| [n - 1, n + 1].into_iter().filter(|n| (0..=10).contains(n));
+/// let s = bfs([3, 8], succ);
+///
+/// assert_eq!(Some(3), s.distance(&0));
+/// assert_eq!(Some(2), s.distance(&10));
+/// assert_eq!(Some(vec![3, 2, 1, 0]), s.path(&0));
+/// let mut two = s.at_distance(2).copied().collect::<Vec<_>>();
+/// two.sort();
+/// assert_eq!(vec![1, 5, 6, 10], two);
+/// ```
+pub fn bfs<N, I, S>(starts: impl IntoIterator<Item = N>, successors: S) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+{
+    bfs_impl(starts, successors, |_| false, usize::MAX)
+}
+
+/// Breadth-first search that stops as soon as a node satisfying `is_goal` is reached
+///
+/// # Examples
+///
+/// ```
+/// use common::search::bfs_to;
+///
+/// let s = bfs_to([1], |&n: &u32| [n + 1, n * 2], |&n| n == 10);
+///
+/// assert_eq!(Some(&10), s.goal());
+/// assert_eq!(Some(4), s.goal_distance());
+/// assert_eq!(Some(vec![1, 2, 4, 5, 10]), s.goal_path());
+/// ```
+pub fn bfs_to<N, I, S, G>(
+    starts: impl IntoIterator<Item = N>,
+    successors: S,
+    is_goal: G,
+) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    bfs_impl(starts, successors, is_goal, usize::MAX)
+}
+
+/// Breadth-first search that does not expand nodes beyond distance `max_depth`
+///
+/// Combine with [`Search::at_distance`] to get all nodes exactly `k` steps away.
+///
+/// # Examples
+///
+/// ```
+/// use common::search::bfs_within;
+///
+/// let s = bfs_within([0], |&n: &i64| [n - 1, n + 1], 3);
+///
+/// assert_eq!(7, s.distances().len());
+/// assert_eq!(2, s.at_distance(3).count());
+/// ```
+pub fn bfs_within<N, I, S>(
+    starts: impl IntoIterator<Item = N>,
+    successors: S,
+    max_depth: usize,
+) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+{
+    bfs_impl(starts, successors, |_| false, max_depth)
+}
+
+fn bfs_impl<N, I, S, G>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: S,
+    mut is_goal: G,
+    max_depth: usize,
+) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if search.dist.contains_key(&start) {
+            continue;
+        }
+        search.dist.insert(start.clone(), 0);
+        queue.push_back(start);
+    }
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        let d = search.dist[&node];
+        if d >= max_depth {
+            continue;
+        }
+
+        for next in successors(&node) {
+            // only enqueue unseen nodes; the first visit is always the shortest in BFS
+            if search.dist.contains_key(&next) {
+                continue;
+            }
+            search.dist.insert(next.clone(), d + 1);
+            search.prev.insert(next.clone(), node.clone());
+            queue.push_back(next);
+        }
+    }
+
+    search
+}
+
+/// Dijkstra's shortest path over the whole reachable graph
+///
+/// `successors` returns `(neighbour, cost)` pairs. Costs must not be negative.
+///
+/// # Examples
+///
+/// ```
+/// use common::search::dijkstra;
+///
+/// let edges = |&n: &char| match n {
+///     'a' => vec![('b', 7), ('c', 2)],
+///     'c' => vec![('b', 3)],
+///     _ => vec![],
+/// };
+/// let s = dijkstra(['a'], edges);
+///
+/// assert_eq!(Some(5), s.distance(&'b'));
+/// assert_eq!(Some(vec!['a', 'c', 'b']), s.path(&'b'));
+/// ```
+pub fn dijkstra<N, C, I, S>(starts: impl IntoIterator<Item = N>, successors: S) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    S: FnMut(&N) -> I,
+{
+    astar_impl(starts, successors, |_| C::default(), |_| false)
+}
+
+/// Dijkstra's shortest path that stops as soon as a node satisfying `is_goal` is settled
+///
+/// The result only holds the nodes settled before the goal, whose distances are final.
+///
+/// # Examples
+///
+/// ```
+/// use common::search::dijkstra_to;
+///
+/// // count to 10, where +1 costs 2 and *3 costs 1
+/// let s = dijkstra_to([1u32], |&n| [(n + 1, 2u32), (n * 3, 1)], |&n| n == 10);
+///
+/// assert_eq!(Some(4), s.goal_distance());
+/// assert_eq!(Some(vec![1, 3, 9, 10]), s.goal_path());
+/// // only settled nodes are reported, not the frontier
+/// assert_eq!(Some(3), s.distance(&27));
+/// assert_eq!(None, s.distance(&28));
+/// ```
+pub fn dijkstra_to<N, C, I, S, G>(
+    starts: impl IntoIterator<Item = N>,
+    successors: S,
+    is_goal: G,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    S: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    astar_impl(starts, successors, |_| C::default(), is_goal)
+}
+
+/// A* search towards a node satisfying `is_goal`
+///
+/// `heuristic` must never overestimate the remaining cost, otherwise the result is not
+/// guaranteed to be the shortest path. It does not have to be consistent: a node that is reached
+/// more cheaply after it has been expanded is expanded again.
+///
+/// # Examples
+///
+/// ```
+/// use common::search::astar;
+///
+/// let goal = (4i32, 4i32);
+/// let s = astar(
+///     [(0, 0)],
+///     |&(x, y)| [((x + 1, y), 1), ((x, y + 1), 1), ((x - 1, y), 1), ((x, y - 1), 1)],
+///     |&(x, y)| (goal.0 - x).abs() + (goal.1 - y).abs(),
+///     |&n| n == goal,
+/// );
+///
+/// assert_eq!(Some(8), s.goal_distance());
+/// assert_eq!(9, s.goal_path().unwrap().len());
+///
+/// // `b` is first expanded via the direct edge, before the cheaper path through `a` is found
+/// let edges = |&n: &char| match n {
+///     's' => vec![('a', 1), ('b', 3)],
+///     'a' => vec![('b', 1)],
+///     'b' => vec![('g', 3)],
+///     _ => vec![],
+/// };
+/// // admissible, but not consistent: h(a) > w(a, b) + h(b)
+/// let h = |&n: &char| if n == 'a' { 4 } else { 0 };
+/// let s = astar(['s'], edges, h, |&n| n == 'g');
+///
+/// assert_eq!(Some(5), s.goal_distance());
+/// assert_eq!(Some(vec!['s', 'a', 'b', 'g']), s.goal_path());
+/// ```
+pub fn astar<N, C, I, S, H, G>(
+    starts: impl IntoIterator<Item = N>,
+    successors: S,
+    heuristic: H,
+    is_goal: G,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    S: FnMut(&N) -> I,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    astar_impl(starts, successors, heuristic, is_goal)
+}
+
+fn astar_impl<N, C, I, S, H, G>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: S,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    S: FnMut(&N) -> I,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let mut search = Search::new();
+    let mut heap = BinaryHeap::new();
+    let mut settled = HashSet::new();
+
+    for start in starts {
+        search.dist.insert(start.clone(), C::default());
+        heap.push(State {
+            priority: heuristic(&start),
+            cost: C::default(),
+            node: start,
+        });
+    }
+
+    while let Some(State { cost, node, .. }) = heap.pop() {
+        // skip stale heap entries for nodes that have since been reached more cheaply; a node
+        // that is reached more cheaply after it was expanded gets expanded again
+        if search.dist.get(&node).is_some_and(|&d| cost > d) {
+            continue;
+        }
+        settled.insert(node.clone());
+
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            if search.dist.get(&next).is_some_and(|&d| next_cost >= d) {
+                continue;
+            }
+            search.dist.insert(next.clone(), next_cost);
+            search.prev.insert(next.clone(), node.clone());
+            heap.push(State {
+                priority: next_cost + heuristic(&next),
+                cost: next_cost,
+                node: next,
+            });
+        }
+    }
+
+    // after stopping early, the frontier only holds tentative distances
+    if search.goal.is_some() {
+        search.dist.retain(|n, _| settled.contains(n));
+        search.prev.retain(|n, _| settled.contains(n));
+    }
+
+    search
+}
+
+/// Heap entry, ordered so that [`BinaryHeap`] pops the lowest priority first
+struct State<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for State<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N, C: Ord> Eq for State<N, C> {}
+
+impl<N, C: Ord> PartialOrd for State<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for State<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // prefer deeper nodes on ties, which lets A* reach the goal sooner
+        other
+            .priority
+            .cmp(&self.priority)
+            .then_with(|| self.cost.cmp(&other.cost))
+    }
+}
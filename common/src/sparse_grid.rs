@@ -0,0 +1,249 @@
+use std::collections::{hash_map, HashMap};
+
+use crate::GridCoord;
+
+/// Axis type used by [`SparseGrid`]. Signed, so grids can grow in any direction.
+pub type SparseAxis = isize;
+
+/// An unbounded 2D grid that only stores occupied cells
+///
+/// The bounding box of all cells ever inserted is tracked incrementally, so it is always
+/// available without scanning the whole map.
+///
+/// # Examples
+///
+/// ```
+/// use common::{GridCoord, SparseGrid};
+///
+/// let mut grid = SparseGrid::new();
+/// grid.insert(GridCoord::new(-1, 0), '#');
+/// grid.insert(GridCoord::new(2, 1), 'o');
+///
+/// assert_eq!(2, grid.len());
+/// assert_eq!(Some((GridCoord::new(-1, 0), GridCoord::new(2, 1))), grid.bounds());
+/// assert_eq!("#...\n...o\n", grid.render(0, |c| c.copied().unwrap_or('.')));
+///
+/// // equality only looks at the cells, not at the bounds left behind by removed ones
+/// let mut other = SparseGrid::new();
+/// other.insert(GridCoord::new(-1, 0), '#');
+/// grid.remove(&GridCoord::new(2, 1));
+/// assert_eq!(other, grid);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<GridCoord<SparseAxis>, T>,
+    bounds: Option<(GridCoord<SparseAxis>, GridCoord<SparseAxis>)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialEq> PartialEq for SparseGrid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cells == other.cells
+    }
+}
+
+impl<T: Eq> Eq for SparseGrid<T> {}
+
+impl<T> SparseGrid<T> {
+    /// Create a new, empty grid
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    /// Number of occupied cells
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Whether there are no occupied cells
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Get the value at `pos`, if any
+    pub fn get(&self, pos: &GridCoord<SparseAxis>) -> Option<&T> {
+        self.cells.get(pos)
+    }
+
+    /// Get a mutable reference to the value at `pos`, if any
+    pub fn get_mut(&mut self, pos: &GridCoord<SparseAxis>) -> Option<&mut T> {
+        self.cells.get_mut(pos)
+    }
+
+    /// Whether `pos` is occupied
+    pub fn contains(&self, pos: &GridCoord<SparseAxis>) -> bool {
+        self.cells.contains_key(pos)
+    }
+
+    /// Set `pos` to `value`, returning the previous value if there was one
+    pub fn insert(&mut self, pos: GridCoord<SparseAxis>, value: T) -> Option<T> {
+        self.extend_bounds(pos);
+        self.cells.insert(pos, value)
+    }
+
+    /// Clear `pos`, returning its value if there was one
+    ///
+    /// The bounding box is **not** shrunk; call [`Self::shrink_bounds`] if that is required.
+    pub fn remove(&mut self, pos: &GridCoord<SparseAxis>) -> Option<T> {
+        self.cells.remove(pos)
+    }
+
+    /// Smallest and largest corner of the bounding box of all inserted cells, inclusive
+    pub fn bounds(&self) -> Option<(GridCoord<SparseAxis>, GridCoord<SparseAxis>)> {
+        self.bounds
+    }
+
+    /// Smallest X coordinate of any inserted cell
+    pub fn min_x(&self) -> Option<SparseAxis> {
+        self.bounds.map(|(min, _)| min.x)
+    }
+
+    /// Largest X coordinate of any inserted cell
+    pub fn max_x(&self) -> Option<SparseAxis> {
+        self.bounds.map(|(_, max)| max.x)
+    }
+
+    /// Smallest Y coordinate of any inserted cell
+    pub fn min_y(&self) -> Option<SparseAxis> {
+        self.bounds.map(|(min, _)| min.y)
+    }
+
+    /// Largest Y coordinate of any inserted cell
+    pub fn max_y(&self) -> Option<SparseAxis> {
+        self.bounds.map(|(_, max)| max.y)
+    }
+
+    /// Recalculate the bounding box from the currently occupied cells
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::{GridCoord, SparseGrid};
+    ///
+    /// let mut grid = SparseGrid::new();
+    /// grid.insert(GridCoord::new(0, 0), ());
+    /// grid.insert(GridCoord::new(5, 5), ());
+    /// grid.remove(&GridCoord::new(5, 5));
+    /// assert_eq!(Some(5), grid.max_x());
+    ///
+    /// grid.shrink_bounds();
+    /// assert_eq!(Some(0), grid.max_x());
+    /// ```
+    pub fn shrink_bounds(&mut self) {
+        self.bounds = None;
+        let keys = self.cells.keys().copied().collect::<Vec<_>>();
+        for pos in keys {
+            self.extend_bounds(pos);
+        }
+    }
+
+    /// Iterate over all occupied cells, in arbitrary order
+    pub fn iter(&self) -> hash_map::Iter<'_, GridCoord<SparseAxis>, T> {
+        self.cells.iter()
+    }
+
+    /// Iterate over all occupied positions, in arbitrary order
+    pub fn positions(&self) -> hash_map::Keys<'_, GridCoord<SparseAxis>, T> {
+        self.cells.keys()
+    }
+
+    /// Render the bounding box plus `padding` cells on every side
+    ///
+    /// `to_char` is called for every cell, with `None` for unoccupied ones. Each row is
+    /// terminated by a newline. An empty grid renders as an empty string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::{GridCoord, SparseGrid};
+    ///
+    /// let mut grid = SparseGrid::new();
+    /// grid.insert(GridCoord::new(0, 0), true);
+    ///
+    /// assert_eq!("...\n.#.\n...\n", grid.render(1, |c| match c {
+    ///     Some(true) => '#',
+    ///     _ => '.',
+    /// }));
+    /// ```
+    pub fn render<F>(&self, padding: usize, mut to_char: F) -> String
+    where
+        F: FnMut(Option<&T>) -> char,
+    {
+        self.render_with(padding, |_, c| to_char(c))
+    }
+
+    /// Like [`Self::render`], but `to_char` also receives the position of each cell
+    ///
+    /// This is useful to draw things not stored in the grid, like a floor or a cursor.
+    pub fn render_with<F>(&self, padding: usize, mut to_char: F) -> String
+    where
+        F: FnMut(GridCoord<SparseAxis>, Option<&T>) -> char,
+    {
+        let Some((min, max)) = self.bounds else {
+            return String::new();
+        };
+        let padding = padding as SparseAxis;
+
+        let mut out = String::new();
+        for y in (min.y - padding)..=(max.y + padding) {
+            for x in (min.x - padding)..=(max.x + padding) {
+                let pos = GridCoord::new(x, y);
+                out.push(to_char(pos, self.cells.get(&pos)));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn extend_bounds(&mut self, pos: GridCoord<SparseAxis>) {
+        self.bounds = Some(match self.bounds {
+            None => (pos, pos),
+            Some((min, max)) => (
+                GridCoord::new(min.x.min(pos.x), min.y.min(pos.y)),
+                GridCoord::new(max.x.max(pos.x), max.y.max(pos.y)),
+            ),
+        });
+    }
+}
+
+impl<T> std::fmt::Display for SparseGrid<T>
+where
+    T: Copy + Into<char>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render(0, |c| c.map_or('.', |&c| c.into())))
+    }
+}
+
+impl<T> FromIterator<(GridCoord<SparseAxis>, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (GridCoord<SparseAxis>, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(GridCoord<SparseAxis>, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (GridCoord<SparseAxis>, T)>>(&mut self, iter: I) {
+        for (pos, value) in iter {
+            self.insert(pos, value);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SparseGrid<T> {
+    type Item = (&'a GridCoord<SparseAxis>, &'a T);
+    type IntoIter = hash_map::Iter<'a, GridCoord<SparseAxis>, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,308 @@
+//! Matching many patterns at once: a prefix tree, and an Aho-Corasick automaton built on it
+//!
+//! Both work on bytes, so `&str` inputs have to be passed as `s.as_bytes()`.
+
+use std::collections::VecDeque;
+
+const ROOT: usize = 0;
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// Outgoing edges, sorted by byte
+    children: Vec<(u8, usize)>,
+    /// The pattern ending at this node
+    pattern: Option<usize>,
+}
+
+impl Node {
+    fn child(&self, byte: u8) -> Option<usize> {
+        self.children
+            .binary_search_by_key(&byte, |&(b, _)| b)
+            .ok()
+            .map(|i| self.children[i].1)
+    }
+}
+
+/// A set of byte strings stored as a prefix tree
+///
+/// Every pattern gets an id, in order of insertion; inserting the same pattern twice returns the
+/// id it already has.
+///
+/// # Examples
+///
+/// ```
+/// use common::text::Trie;
+///
+/// let towels: Trie = ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"].into_iter().collect();
+///
+/// assert!(towels.contains(b"bwu"));
+/// assert!(!towels.contains(b"bw"));
+/// assert_eq!(Some(4), towels.get(b"bwu"));
+///
+/// // patterns that are prefixes of the haystack, as (id, length)
+/// let found: Vec<_> = towels.prefixes(b"brwrr").collect();
+/// assert_eq!(vec![(2, 1), (7, 2)], found);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Trie {
+    nodes: Vec<Node>,
+    lengths: Vec<usize>,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node::default()],
+            lengths: Vec::new(),
+        }
+    }
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a pattern, returning its id
+    pub fn insert(&mut self, pattern: impl AsRef<[u8]>) -> usize {
+        let pattern = pattern.as_ref();
+        let mut node = ROOT;
+        for &byte in pattern {
+            node = match self.nodes[node].child(byte) {
+                Some(next) => next,
+                None => {
+                    let next = self.nodes.len();
+                    self.nodes.push(Node::default());
+                    let children = &mut self.nodes[node].children;
+                    let i = children.partition_point(|&(b, _)| b < byte);
+                    children.insert(i, (byte, next));
+                    next
+                }
+            };
+        }
+
+        *self.nodes[node].pattern.get_or_insert_with(|| {
+            self.lengths.push(pattern.len());
+            self.lengths.len() - 1
+        })
+    }
+
+    /// Number of distinct patterns
+    pub fn len(&self) -> usize {
+        self.lengths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    /// Length of the pattern with id `id`
+    pub fn pattern_len(&self, id: usize) -> usize {
+        self.lengths[id]
+    }
+
+    /// The id of `word`, if it is one of the patterns
+    pub fn get(&self, word: impl AsRef<[u8]>) -> Option<usize> {
+        word.as_ref()
+            .iter()
+            .try_fold(ROOT, |node, &b| self.nodes[node].child(b))
+            .and_then(|node| self.nodes[node].pattern)
+    }
+
+    pub fn contains(&self, word: impl AsRef<[u8]>) -> bool {
+        self.get(word).is_some()
+    }
+
+    /// All patterns that are prefixes of `haystack`, as `(id, length)`, shortest first
+    pub fn prefixes<'a>(&'a self, haystack: &'a [u8]) -> impl Iterator<Item = (usize, usize)> + 'a {
+        haystack
+            .iter()
+            .scan(ROOT, |node, &b| {
+                *node = self.nodes[*node].child(b)?;
+                Some(*node)
+            })
+            .enumerate()
+            .filter_map(|(i, node)| Some((self.nodes[node].pattern?, i + 1)))
+    }
+
+    /// Number of ways to write `s` as a concatenation of patterns
+    ///
+    /// The empty string can be written in exactly one way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use common::text::Trie;
+    ///
+    /// // 2024 day 19
+    /// let towels: Trie = ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"].into_iter().collect();
+    ///
+    /// assert_eq!(2, towels.segmentations(b"brwrr"));
+    /// assert_eq!(6, towels.segmentations(b"rrbgbr"));
+    /// assert_eq!(0, towels.segmentations(b"ubwu"));
+    /// ```
+    pub fn segmentations(&self, s: &[u8]) -> u64 {
+        // ways[i]: number of ways to segment s[i..]
+        let mut ways = vec![0; s.len() + 1];
+        ways[s.len()] = 1;
+        for i in (0..s.len()).rev() {
+            ways[i] = self.prefixes(&s[i..]).map(|(_, len)| ways[i + len]).sum();
+        }
+        ways[0]
+    }
+}
+
+impl<S: AsRef<[u8]>> FromIterator<S> for Trie {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        let mut trie = Self::new();
+        for pattern in iter {
+            trie.insert(pattern);
+        }
+        trie
+    }
+}
+
+/// An occurrence of a pattern in a haystack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    /// The id of the pattern
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Finds all occurrences of many patterns in a single pass over the haystack
+///
+/// # Examples
+///
+/// ```
+/// use common::text::AhoCorasick;
+///
+/// let digits = AhoCorasick::new(["one", "two", "eight"]);
+/// let found: Vec<_> = digits
+///     .find_overlapping(b"eightwone")
+///     .map(|m| (m.pattern, m.start))
+///     .collect();
+/// assert_eq!(vec![(2, 0), (1, 4), (0, 6)], found);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AhoCorasick {
+    trie: Trie,
+    /// Longest proper suffix of each node that is also a node
+    fail: Vec<usize>,
+    /// Longest proper suffix of each node that ends a pattern
+    dict: Vec<Option<usize>>,
+}
+
+impl AhoCorasick {
+    /// Build the automaton; patterns get ids in order, as in [`Trie::insert`]
+    pub fn new<S: AsRef<[u8]>>(patterns: impl IntoIterator<Item = S>) -> Self {
+        Self::from(patterns.into_iter().collect::<Trie>())
+    }
+
+    pub fn trie(&self) -> &Trie {
+        &self.trie
+    }
+
+    /// All matches, including overlapping ones, ordered by end position
+    ///
+    /// Matches that end at the same position are reported longest first.
+    pub fn find_overlapping<'a>(&'a self, haystack: &'a [u8]) -> Matches<'a> {
+        Matches {
+            ac: self,
+            haystack,
+            pos: 0,
+            state: ROOT,
+            pending: None,
+        }
+    }
+
+    /// Whether any pattern occurs in `haystack`
+    pub fn is_match(&self, haystack: &[u8]) -> bool {
+        self.find_overlapping(haystack).next().is_some()
+    }
+
+    fn next_state(&self, mut state: usize, byte: u8) -> usize {
+        loop {
+            if let Some(next) = self.trie.nodes[state].child(byte) {
+                return next;
+            }
+            if state == ROOT {
+                return ROOT;
+            }
+            state = self.fail[state];
+        }
+    }
+
+    /// The node itself if it ends a pattern, otherwise the next one along its suffixes
+    fn first_output(&self, state: usize) -> Option<usize> {
+        match self.trie.nodes[state].pattern {
+            Some(_) => Some(state),
+            None => self.dict[state],
+        }
+    }
+}
+
+impl From<Trie> for AhoCorasick {
+    fn from(trie: Trie) -> Self {
+        let mut fail = vec![ROOT; trie.nodes.len()];
+        let mut dict = vec![None; trie.nodes.len()];
+
+        // breadth first, so the links of all shorter nodes are known
+        let mut queue: VecDeque<_> = trie.nodes[ROOT].children.iter().map(|&(_, n)| n).collect();
+        while let Some(node) = queue.pop_front() {
+            for &(byte, child) in &trie.nodes[node].children {
+                let mut f = fail[node];
+                fail[child] = loop {
+                    match trie.nodes[f].child(byte) {
+                        Some(next) if next != child => break next,
+                        _ if f == ROOT => break ROOT,
+                        _ => f = fail[f],
+                    }
+                };
+                let suffix = fail[child];
+                dict[child] = match trie.nodes[suffix].pattern {
+                    Some(_) => Some(suffix),
+                    None => dict[suffix],
+                };
+                queue.push_back(child);
+            }
+        }
+
+        Self { trie, fail, dict }
+    }
+}
+
+/// Iterator over the matches of an [`AhoCorasick`] automaton
+#[derive(Debug, Clone)]
+pub struct Matches<'a> {
+    ac: &'a AhoCorasick,
+    haystack: &'a [u8],
+    pos: usize,
+    state: usize,
+    /// Next node with a pattern ending at `pos`
+    pending: Option<usize>,
+}
+
+impl Iterator for Matches<'_> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Match> {
+        loop {
+            if let Some(node) = self.pending {
+                self.pending = self.ac.dict[node];
+                let pattern = self.ac.trie.nodes[node].pattern.unwrap();
+                return Some(Match {
+                    pattern,
+                    start: self.pos - self.ac.trie.lengths[pattern],
+                    end: self.pos,
+                });
+            }
+
+            let &byte = self.haystack.get(self.pos)?;
+            self.state = self.ac.next_state(self.state, byte);
+            self.pos += 1;
+            self.pending = self.ac.first_output(self.state);
+        }
+    }
+}
@@ -0,0 +1,365 @@
+//! A small framework for register machines
+//!
+//! A machine only has to define its instructions by implementing [`InstructionSet`]; the
+//! [`Machine`] takes care of the program counter, jumps, cycle counting, I/O queues and tracing.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::fmt;
+//!
+//! use common::vm::{Cpu, Flow, InstructionSet, Machine};
+//!
+//! // 2015 day 23
+//! enum Op {
+//!     Hlf(usize),
+//!     Tpl(usize),
+//!     Inc(usize),
+//!     Jmp(isize),
+//!     Jie(usize, isize),
+//!     Jio(usize, isize),
+//! }
+//!
+//! impl InstructionSet for Op {
+//!     type Word = u64;
+//!     const REGISTERS: &'static [&'static str] = &["a", "b"];
+//!
+//!     fn parse(line: &str) -> Self {
+//!         let (op, args) = line.split_once(' ').unwrap();
+//!         let args: Vec<_> = args.split(", ").collect();
+//!         let reg = |i: usize| Self::register(args[i]).unwrap();
+//!         let offset = |i: usize| args[i].parse().unwrap();
+//!         match op {
+//!             "hlf" => Op::Hlf(reg(0)),
+//!             "tpl" => Op::Tpl(reg(0)),
+//!             "inc" => Op::Inc(reg(0)),
+//!             "jmp" => Op::Jmp(offset(0)),
+//!             "jie" => Op::Jie(reg(0), offset(1)),
+//!             "jio" => Op::Jio(reg(0), offset(1)),
+//!             _ => panic!("unknown instruction {line:?}"),
+//!         }
+//!     }
+//!
+//!     fn execute(&self, cpu: &mut Cpu<u64>) -> Flow {
+//!         match *self {
+//!             Op::Hlf(r) => cpu.regs[r] /= 2,
+//!             Op::Tpl(r) => cpu.regs[r] *= 3,
+//!             Op::Inc(r) => cpu.regs[r] += 1,
+//!             Op::Jmp(o) => return Flow::Jump(o),
+//!             Op::Jie(r, o) if cpu.regs[r] % 2 == 0 => return Flow::Jump(o),
+//!             Op::Jio(r, o) if cpu.regs[r] == 1 => return Flow::Jump(o),
+//!             Op::Jie(..) | Op::Jio(..) => {}
+//!         }
+//!         Flow::Next
+//!     }
+//! }
+//!
+//! impl fmt::Display for Op {
+//!     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         let name = |r: usize| Self::REGISTERS[r];
+//!         match *self {
+//!             Op::Hlf(r) => write!(f, "hlf {}", name(r)),
+//!             Op::Tpl(r) => write!(f, "tpl {}", name(r)),
+//!             Op::Inc(r) => write!(f, "inc {}", name(r)),
+//!             Op::Jmp(o) => write!(f, "jmp {o:+}"),
+//!             Op::Jie(r, o) => write!(f, "jie {}, {o:+}", name(r)),
+//!             Op::Jio(r, o) => write!(f, "jio {}, {o:+}", name(r)),
+//!         }
+//!     }
+//! }
+//!
+//! let mut vm = Machine::<Op>::parse("inc a\njio a, +2\ntpl a\ninc a\n").with_trace();
+//! vm.run();
+//! assert_eq!(2, vm.reg("a"));
+//!
+//! let trace = vm.trace().unwrap();
+//! assert_eq!(3, trace.len());
+//! assert!(trace[1].starts_with("   1: jio a, +2") && trace[1].ends_with("a=1 b=0"));
+//! assert_eq!("   0: inc a\n   1: jio a, +2\n   2: tpl a\n   3: inc a\n", vm.disassemble());
+//! ```
+
+use std::{collections::VecDeque, fmt::Display, str::FromStr};
+
+/// The instructions of a machine
+pub trait InstructionSet: Sized + Display {
+    /// The type of values held in registers
+    type Word: Copy + Default + Display;
+
+    /// Names of the registers, in index order
+    const REGISTERS: &'static [&'static str];
+
+    /// Parse a single line of the program
+    fn parse(line: &str) -> Self;
+
+    /// Execute the instruction, returning where to continue
+    fn execute(&self, cpu: &mut Cpu<Self::Word>) -> Flow;
+
+    /// Number of cycles the instruction takes
+    fn cycles(&self) -> usize {
+        1
+    }
+
+    /// The index of the register called `name`
+    fn register(name: &str) -> Option<usize> {
+        Self::REGISTERS.iter().position(|&r| r == name)
+    }
+}
+
+/// Where to continue after executing an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// Continue with the next instruction
+    Next,
+    /// Jump relative to the current instruction
+    Jump(isize),
+    /// Jump to an absolute address
+    Goto(usize),
+    /// Stop the machine
+    Halt,
+    /// Wait for input; the same instruction is executed again once input has been provided
+    Wait,
+}
+
+/// An instruction argument that is either a register or an immediate value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand<W> {
+    Reg(usize),
+    Imm(W),
+}
+
+impl<W: FromStr> Operand<W> {
+    /// Parse a register name from `registers`, or an immediate value
+    ///
+    /// # Panics
+    ///
+    /// Panics if `s` is neither.
+    pub fn parse(s: &str, registers: &[&str]) -> Self {
+        match registers.iter().position(|&r| r == s) {
+            Some(i) => Operand::Reg(i),
+            None => Operand::Imm(
+                s.parse()
+                    .unwrap_or_else(|_| panic!("neither a register nor a value: {s:?}")),
+            ),
+        }
+    }
+}
+
+/// The state of a machine that instructions operate on
+#[derive(Debug, Clone)]
+pub struct Cpu<W> {
+    /// Register values, indexed as in [`InstructionSet::REGISTERS`]
+    pub regs: Vec<W>,
+    /// Values waiting to be read by the program
+    pub input: VecDeque<W>,
+    /// Values written by the program
+    pub output: Vec<W>,
+    pc: usize,
+    cycle: usize,
+}
+
+impl<W: Copy + Default> Cpu<W> {
+    fn new(registers: usize) -> Self {
+        Self {
+            regs: vec![W::default(); registers],
+            input: VecDeque::new(),
+            output: Vec::new(),
+            pc: 0,
+            cycle: 0,
+        }
+    }
+
+    /// The address of the current instruction
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// Number of cycles started so far; while a hook runs, this is the current cycle (1-based)
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    /// The value of an operand
+    pub fn value(&self, op: Operand<W>) -> W {
+        match op {
+            Operand::Reg(r) => self.regs[r],
+            Operand::Imm(v) => v,
+        }
+    }
+}
+
+/// A program together with the state of the machine running it
+#[derive(Debug, Clone)]
+pub struct Machine<I: InstructionSet> {
+    program: Vec<I>,
+    cpu: Cpu<I::Word>,
+    halted: bool,
+    trace: Option<Vec<String>>,
+}
+
+impl<I: InstructionSet> Machine<I> {
+    pub fn new(program: Vec<I>) -> Self {
+        Self {
+            program,
+            cpu: Cpu::new(I::REGISTERS.len()),
+            halted: false,
+            trace: None,
+        }
+    }
+
+    /// Parse a program with one instruction per line, ignoring blank lines
+    pub fn parse(source: &str) -> Self {
+        Self::new(
+            source
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(I::parse)
+                .collect(),
+        )
+    }
+
+    /// Set the initial value of a register
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no register called `name`.
+    pub fn with_register(mut self, name: &str, value: I::Word) -> Self {
+        self.set_reg(name, value);
+        self
+    }
+
+    /// Queue up values for the program to read
+    pub fn with_input(mut self, input: impl IntoIterator<Item = I::Word>) -> Self {
+        self.cpu.input.extend(input);
+        self
+    }
+
+    /// Record a line for every executed instruction, see [`Self::trace`]
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    pub fn program(&self) -> &[I] {
+        &self.program
+    }
+
+    pub fn cpu(&self) -> &Cpu<I::Word> {
+        &self.cpu
+    }
+
+    pub fn cpu_mut(&mut self) -> &mut Cpu<I::Word> {
+        &mut self.cpu
+    }
+
+    /// The value of the register called `name`
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such register.
+    pub fn reg(&self, name: &str) -> I::Word {
+        self.cpu.regs[Self::register(name)]
+    }
+
+    /// Set the register called `name`
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such register.
+    pub fn set_reg(&mut self, name: &str, value: I::Word) {
+        let r = Self::register(name);
+        self.cpu.regs[r] = value;
+    }
+
+    fn register(name: &str) -> usize {
+        I::register(name).unwrap_or_else(|| panic!("unknown register {name:?}"))
+    }
+
+    /// Values written by the program so far
+    pub fn output(&self) -> &[I::Word] {
+        &self.cpu.output
+    }
+
+    /// Whether the program has stopped, either by halting or by jumping outside of it
+    pub fn halted(&self) -> bool {
+        self.halted || self.cpu.pc >= self.program.len()
+    }
+
+    /// Execute a single instruction; returns `false` if the machine was halted or is waiting for
+    /// input
+    pub fn step(&mut self) -> bool {
+        self.step_with(&mut |_| {})
+    }
+
+    /// Like [`Self::step`], calling `hook` at the start of every cycle the instruction takes
+    ///
+    /// The hook sees the state from before the instruction took effect. An instruction that waits
+    /// for input triggers the hook again for the same cycles once it is retried.
+    pub fn step_with(&mut self, hook: &mut impl FnMut(&Cpu<I::Word>)) -> bool {
+        if self.halted() {
+            return false;
+        }
+
+        let instruction = &self.program[self.cpu.pc];
+        let before = self.cpu.cycle;
+        for _ in 0..instruction.cycles() {
+            self.cpu.cycle += 1;
+            hook(&self.cpu);
+        }
+
+        let pc = self.cpu.pc;
+        match instruction.execute(&mut self.cpu) {
+            Flow::Next => self.cpu.pc += 1,
+            Flow::Jump(offset) => match self.cpu.pc.checked_add_signed(offset) {
+                Some(pc) => self.cpu.pc = pc,
+                None => self.halted = true,
+            },
+            Flow::Goto(pc) => self.cpu.pc = pc,
+            Flow::Halt => self.halted = true,
+            Flow::Wait => {
+                // the instruction did not actually run
+                self.cpu.cycle = before;
+                return false;
+            }
+        }
+
+        if let Some(trace) = &mut self.trace {
+            let regs: Vec<_> = I::REGISTERS
+                .iter()
+                .zip(&self.cpu.regs)
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect();
+            let listing = format!("{pc:4}: {instruction}");
+            trace.push(format!("{listing:<24} {}", regs.join(" ")));
+        }
+
+        true
+    }
+
+    /// Run until the program halts or waits for input
+    pub fn run(&mut self) {
+        while self.step() {}
+    }
+
+    /// Run until the program halts or waits for input, calling `hook` at the start of every cycle
+    pub fn run_with(&mut self, mut hook: impl FnMut(&Cpu<I::Word>)) {
+        while self.step_with(&mut hook) {}
+    }
+
+    /// The recorded trace, if enabled with [`Self::with_trace`]
+    ///
+    /// Each line holds the address and the instruction, followed by the registers after it ran.
+    /// An instruction that waits for input is only recorded once it actually runs.
+    pub fn trace(&self) -> Option<&[String]> {
+        self.trace.as_deref()
+    }
+
+    /// A listing of the program, one instruction per line, prefixed with its address
+    pub fn disassemble(&self) -> String {
+        self.program
+            .iter()
+            .enumerate()
+            .map(|(pc, instruction)| format!("{pc:4}: {instruction}\n"))
+            .collect()
+    }
+}